@@ -15,11 +15,12 @@ let initialize_global_vector_pool: (max_size: number, vector_size: number) => vo
 let get_global_vector: () => number | undefined;
 let release_global_vector: (index: number) => boolean;
 let get_global_pool_stats: () => any[];
-let batch_normalize_vectors: (vectors: Float32Array) => Float32Array;
+let batch_normalize_vectors: (vectors: Float32Array, skipInvalid?: boolean) => Float32Array;
 let compute_diversity_scores: (vectors: Float32Array, threshold: number) => Float32Array;
 let apply_temporal_decay: (scores: Float32Array, timestamps: BigUint64Array) => Float32Array;
 let batch_privacy_filter: (post_user_ids: Uint32Array, is_private_flags: Uint8Array, user_permission: any) => Uint32Array;
 let Vector1024: any;
+let VectorN: any;
 let DiscoveryScorer: any;
 let BatchProcessor: any;
 let VectorPool: any;
@@ -75,6 +76,7 @@ async function initializeWasmModule(): Promise<void> {
       apply_temporal_decay = wasmModule.apply_temporal_decay;
       batch_privacy_filter = wasmModule.batch_privacy_filter;
       Vector1024 = wasmModule.Vector1024;
+      VectorN = wasmModule.VectorN;
       DiscoveryScorer = wasmModule.DiscoveryScorer;
       BatchProcessor = wasmModule.BatchProcessor;
       VectorPool = wasmModule.VectorPool;
@@ -263,11 +265,11 @@ export class ManagedBatchProcessor {
       throw new Error('Batch processor has been disposed');
     }
 
-    const query = new ManagedVector1024(queryVector);
+    const query = new VectorN(queryVector);
     try {
-      return this.processor.process_similarity_batch(query.instance, vectorsData);
+      return this.processor.process_similarity_batch(query, vectorsData);
     } finally {
-      query.dispose();
+      query.free();
     }
   }
 
@@ -280,11 +282,11 @@ export class ManagedBatchProcessor {
       throw new Error('Batch processor has been disposed');
     }
 
-    const query = new ManagedVector1024(queryVector);
+    const query = new VectorN(queryVector);
     try {
-      return this.processor.find_top_k_similar(query.instance, vectorsData, k);
+      return this.processor.find_top_k_similar(query, vectorsData, k);
     } finally {
-      query.dispose();
+      query.free();
     }
   }
 
@@ -91,7 +91,7 @@ export class WasmVectorService implements IWasmVectorService {
 
     try {
       const processor = new this.wasmModule.BatchProcessor(100);
-      const queryVec = new this.wasmModule.Vector1024(queryVector);
+      const queryVec = new this.wasmModule.VectorN(queryVector);
       
       return processor.find_top_k_similar(queryVec, candidateVectors, k);
     } catch (error) {
@@ -1,3 +1,5 @@
+use aes_gcm::aead::Aead;
+use aes_gcm::{Aes256Gcm, Nonce};
 use base64::{engine::general_purpose, Engine as _};
 use hmac::{Hmac, Mac};
 use rand::Rng;
@@ -12,7 +14,7 @@ pub struct CryptoProcessor {
     // No need for persistent RNG state in WASM
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct JwtPayload {
     pub sub: String,
     pub exp: u64,
@@ -20,6 +22,30 @@ pub struct JwtPayload {
     pub iss: String,
 }
 
+#[derive(Deserialize)]
+struct JwtHeader {
+    alg: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JwtError {
+    Malformed(String),
+    UnsupportedAlgorithm(String),
+    InvalidSignature,
+    Expired,
+}
+
+impl std::fmt::Display for JwtError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JwtError::Malformed(reason) => write!(f, "Invalid JWT: {}", reason),
+            JwtError::UnsupportedAlgorithm(alg) => write!(f, "Unsupported JWT algorithm: {}", alg),
+            JwtError::InvalidSignature => write!(f, "Invalid JWT signature"),
+            JwtError::Expired => write!(f, "JWT has expired"),
+        }
+    }
+}
+
 #[wasm_bindgen]
 impl CryptoProcessor {
     #[wasm_bindgen(constructor)]
@@ -89,6 +115,18 @@ impl CryptoProcessor {
         Ok(bytes)
     }
 
+    #[wasm_bindgen]
+    pub fn encrypt_aes_gcm(&self, plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.aes_gcm_encrypt(plaintext, key)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    #[wasm_bindgen]
+    pub fn decrypt_aes_gcm(&self, ciphertext: &[u8], key: &[u8]) -> Result<Vec<u8>, JsValue> {
+        self.aes_gcm_decrypt(ciphertext, key)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
     #[wasm_bindgen]
     pub fn generate_random_hex(&self, length: usize) -> Result<String, JsValue> {
         let bytes = self.generate_random_bytes(length)?;
@@ -173,6 +211,7 @@ impl CryptoProcessor {
     }
 
     #[wasm_bindgen]
+    #[allow(clippy::too_many_arguments)]
     pub fn verify_request_signature(
         &self,
         method: &str,
@@ -205,47 +244,178 @@ impl CryptoProcessor {
 
     #[wasm_bindgen]
     pub fn create_jwt(&self, payload: &str, secret: &[u8]) -> Result<String, JsValue> {
+        self.sign_jwt_json(payload, secret)
+            .map_err(|e| JsValue::from_str(&e))
+    }
+
+    // Checks the algorithm, signature and expiry; use verify_jwt_claims to get the claims back
+    #[wasm_bindgen]
+    pub fn verify_jwt(&self, token: &str, secret: &[u8]) -> bool {
+        self.verify_jwt_at(token, secret, unix_now()).is_ok()
+    }
+
+    // Verifies signature and expiry, returning the decoded claims as a JS object
+    #[wasm_bindgen]
+    pub fn verify_jwt_claims(&self, token: &str, secret: &[u8]) -> Result<JsValue, JsValue> {
+        let payload = self
+            .verify_jwt_at(token, secret, unix_now())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+        serde_wasm_bindgen::to_value(&payload)
+            .map_err(|e| JsValue::from_str(&format!("Failed to convert claims: {}", e)))
+    }
+
+    #[wasm_bindgen]
+    pub fn decode_jwt_payload(&self, token: &str) -> Result<String, JsValue> {
+        let parts: Vec<&str> = token.split('.').collect();
+        if parts.len() != 3 {
+            return Err(JsValue::from_str("Invalid JWT format"));
+        }
+
+        let payload_bytes = decode_base64url_segment(parts[1])
+            .map_err(|e| JsValue::from_str(&format!("Invalid JWT payload: {}", e)))?;
+
+        String::from_utf8(payload_bytes)
+            .map_err(|e| JsValue::from_str(&format!("Invalid UTF-8: {}", e)))
+    }
+}
+
+// Synchronous JWT helpers usable without Web Crypto or a JS clock
+impl CryptoProcessor {
+    pub fn sign_jwt(&self, payload: &JwtPayload, secret: &[u8]) -> Result<String, String> {
+        let payload_json = serde_json::to_string(payload)
+            .map_err(|e| format!("Failed to serialize JWT payload: {}", e))?;
+
+        self.sign_jwt_json(&payload_json, secret)
+    }
+
+    // Shared by create_jwt and sign_jwt so both emit the same header and encoding
+    fn sign_jwt_json(&self, payload_json: &str, secret: &[u8]) -> Result<String, String> {
         let header = r#"{"alg":"HS256","typ":"JWT"}"#;
         let header_b64 = general_purpose::URL_SAFE_NO_PAD.encode(header.as_bytes());
-        let payload_b64 = general_purpose::URL_SAFE_NO_PAD.encode(payload.as_bytes());
+        let payload_b64 = general_purpose::URL_SAFE_NO_PAD.encode(payload_json.as_bytes());
 
         let message = format!("{}.{}", header_b64, payload_b64);
-        let signature = self.hmac_sha256(secret, message.as_bytes())?;
-        let signature_b64 = general_purpose::URL_SAFE_NO_PAD.encode(signature);
+        let mut mac =
+            HmacSha256::new_from_slice(secret).map_err(|e| format!("Invalid key length: {}", e))?;
+        mac.update(message.as_bytes());
+        let signature_b64 = general_purpose::URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
 
         Ok(format!("{}.{}", message, signature_b64))
     }
 
-    #[wasm_bindgen]
-    pub fn verify_jwt(&self, token: &str, secret: &[u8]) -> bool {
+    // `now` is the current Unix time in seconds; tokens with `exp <= now` are rejected
+    pub fn verify_jwt_at(
+        &self,
+        token: &str,
+        secret: &[u8],
+        now: u64,
+    ) -> Result<JwtPayload, JwtError> {
+        let payload_segment = self.verify_jwt_signature(token, secret)?;
+
+        let payload_bytes = decode_base64url_segment(payload_segment)
+            .map_err(|e| JwtError::Malformed(format!("payload: {}", e)))?;
+        let payload: JwtPayload = serde_json::from_slice(&payload_bytes)
+            .map_err(|e| JwtError::Malformed(format!("claims: {}", e)))?;
+
+        if payload.exp <= now {
+            return Err(JwtError::Expired);
+        }
+
+        Ok(payload)
+    }
+
+    // Only HS256 is accepted so a forged header cannot pick the algorithm
+    // (e.g. "none"). Returns the payload segment once the signature checks out.
+    fn verify_jwt_signature<'a>(&self, token: &'a str, secret: &[u8]) -> Result<&'a str, JwtError> {
         let parts: Vec<&str> = token.split('.').collect();
         if parts.len() != 3 {
-            return false;
+            return Err(JwtError::Malformed("expected three segments".to_string()));
         }
 
-        let message = format!("{}.{}", parts[0], parts[1]);
-        if let Ok(expected_signature) = self.hmac_sha256(secret, message.as_bytes()) {
-            if let Ok(provided_signature) = general_purpose::URL_SAFE_NO_PAD.decode(parts[2]) {
-                return expected_signature == provided_signature;
-            }
+        let header_bytes = decode_base64url_segment(parts[0])
+            .map_err(|e| JwtError::Malformed(format!("header: {}", e)))?;
+        let header: JwtHeader = serde_json::from_slice(&header_bytes)
+            .map_err(|e| JwtError::Malformed(format!("header: {}", e)))?;
+        if header.alg != "HS256" {
+            return Err(JwtError::UnsupportedAlgorithm(header.alg));
         }
-        false
+
+        let signature =
+            decode_base64url_segment(parts[2]).map_err(|_| JwtError::InvalidSignature)?;
+        let message = format!("{}.{}", parts[0], parts[1]);
+        let mut mac = HmacSha256::new_from_slice(secret)
+            .map_err(|e| JwtError::Malformed(format!("invalid key length: {}", e)))?;
+        mac.update(message.as_bytes());
+        mac.verify_slice(&signature)
+            .map_err(|_| JwtError::InvalidSignature)?;
+
+        Ok(parts[1])
     }
+}
 
-    #[wasm_bindgen]
-    pub fn decode_jwt_payload(&self, token: &str) -> Result<String, JsValue> {
-        let parts: Vec<&str> = token.split('.').collect();
-        if parts.len() != 3 {
-            return Err(JsValue::from_str("Invalid JWT format"));
+const AES_GCM_NONCE_LEN: usize = 12;
+
+// AES-256-GCM; the output is a random 12-byte nonce followed by the
+// ciphertext and its authentication tag
+impl CryptoProcessor {
+    pub fn aes_gcm_encrypt(&self, plaintext: &[u8], key: &[u8]) -> Result<Vec<u8>, String> {
+        let cipher = aes_gcm_cipher(key)?;
+
+        let mut nonce = [0u8; AES_GCM_NONCE_LEN];
+        rand::thread_rng().fill(&mut nonce[..]);
+
+        let ciphertext = cipher
+            .encrypt(Nonce::from_slice(&nonce), plaintext)
+            .map_err(|_| "Encryption failed".to_string())?;
+
+        let mut output = Vec::with_capacity(AES_GCM_NONCE_LEN + ciphertext.len());
+        output.extend_from_slice(&nonce);
+        output.extend_from_slice(&ciphertext);
+        Ok(output)
+    }
+
+    pub fn aes_gcm_decrypt(&self, ciphertext: &[u8], key: &[u8]) -> Result<Vec<u8>, String> {
+        let cipher = aes_gcm_cipher(key)?;
+
+        if ciphertext.len() < AES_GCM_NONCE_LEN {
+            return Err("Ciphertext is too short".to_string());
         }
+        let (nonce, sealed) = ciphertext.split_at(AES_GCM_NONCE_LEN);
 
-        let payload_bytes = general_purpose::URL_SAFE_NO_PAD
-            .decode(parts[1])
-            .map_err(|e| JsValue::from_str(&format!("Invalid base64: {}", e)))?;
+        cipher
+            .decrypt(Nonce::from_slice(nonce), sealed)
+            .map_err(|_| {
+                "Decryption failed: ciphertext is invalid or was tampered with".to_string()
+            })
+    }
+}
 
-        String::from_utf8(payload_bytes)
-            .map_err(|e| JsValue::from_str(&format!("Invalid UTF-8: {}", e)))
+fn aes_gcm_cipher(key: &[u8]) -> Result<Aes256Gcm, String> {
+    if key.len() != 32 {
+        return Err(format!("AES-GCM key must be 32 bytes, got {}", key.len()));
     }
+    <Aes256Gcm as aes_gcm::KeyInit>::new_from_slice(key).map_err(|e| format!("Invalid key: {}", e))
+}
+
+impl Default for CryptoProcessor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Current Unix time in seconds; js_sys::Date is only available inside a JS host
+#[cfg(target_arch = "wasm32")]
+fn unix_now() -> u64 {
+    js_sys::Date::now() as u64 / 1000
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
 }
 
 // Utility functions
@@ -278,9 +448,53 @@ pub fn base64_url_encode(data: &[u8]) -> String {
 
 #[wasm_bindgen]
 pub fn base64_url_decode(b64_str: &str) -> Result<Vec<u8>, JsValue> {
+    decode_base64url(b64_str).map_err(|e| JsValue::from_str(&e))
+}
+
+// JWT segments are never empty, so on top of decode_base64url an empty segment
+// is rejected
+pub fn decode_base64url_segment(segment: &str) -> Result<Vec<u8>, String> {
+    if segment.is_empty() {
+        return Err("Invalid base64url: empty segment".to_string());
+    }
+
+    decode_base64url(segment)
+}
+
+// Strict base64url decoding: validates the alphabet and length up front so
+// malformed input gets a descriptive error instead of a generic one. Empty input
+// decodes to no bytes.
+pub fn decode_base64url(segment: &str) -> Result<Vec<u8>, String> {
+    // Optional trailing padding is accepted, but only where it belongs
+    let unpadded = segment.trim_end_matches('=');
+    let padding = segment.len() - unpadded.len();
+    if padding > 0 && (padding > 2 || segment.len() % 4 != 0) {
+        return Err(format!(
+            "Invalid base64url: malformed padding in segment of length {}",
+            segment.len()
+        ));
+    }
+
+    if let Some((position, c)) = unpadded
+        .char_indices()
+        .find(|(_, c)| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_'))
+    {
+        return Err(format!(
+            "Invalid base64url: illegal character {:?} at position {}",
+            c, position
+        ));
+    }
+
+    if unpadded.len() % 4 == 1 {
+        return Err(format!(
+            "Invalid base64url: length {} is not a valid encoded length",
+            unpadded.len()
+        ));
+    }
+
     general_purpose::URL_SAFE_NO_PAD
-        .decode(b64_str)
-        .map_err(|e| JsValue::from_str(&format!("Invalid base64url: {}", e)))
+        .decode(unpadded)
+        .map_err(|e| format!("Invalid base64url: {}", e))
 }
 
 // Performance comparison functions
@@ -312,3 +526,228 @@ pub fn benchmark_sha256_operations(iterations: u32) -> f64 {
 
     (end - start) / iterations as f64
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &[u8] = b"test-secret-key";
+    // 2100-01-01, for tokens that must still be live when checked against the clock
+    const FAR_FUTURE_EXP: u64 = 4_102_444_800;
+
+    fn sample_payload(exp: u64) -> JwtPayload {
+        JwtPayload {
+            sub: "user-123".to_string(),
+            exp,
+            iat: 1_700_000_000,
+            iss: "capture".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify_jwt() {
+        let processor = CryptoProcessor::new();
+        let payload = sample_payload(FAR_FUTURE_EXP);
+        let token = processor.sign_jwt(&payload, SECRET).unwrap();
+
+        assert!(processor.verify_jwt(&token, SECRET));
+        assert_eq!(
+            processor
+                .verify_jwt_at(&token, SECRET, 1_700_000_100)
+                .unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn test_create_jwt_matches_sign_jwt() {
+        let processor = CryptoProcessor::new();
+        let payload = sample_payload(1_700_000_900);
+        let payload_json = serde_json::to_string(&payload).unwrap();
+
+        assert_eq!(
+            processor.create_jwt(&payload_json, SECRET).unwrap(),
+            processor.sign_jwt(&payload, SECRET).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_verify_jwt_rejects_tampered_signature() {
+        let processor = CryptoProcessor::new();
+        let token = processor
+            .sign_jwt(&sample_payload(FAR_FUTURE_EXP), SECRET)
+            .unwrap();
+
+        let (message, signature) = token.rsplit_once('.').unwrap();
+        let mut signature_bytes = decode_base64url_segment(signature).unwrap();
+        signature_bytes[0] ^= 0x01;
+        let tampered = format!("{}.{}", message, base64_url_encode(&signature_bytes));
+
+        assert_eq!(
+            processor.verify_jwt_at(&tampered, SECRET, 1_700_000_100),
+            Err(JwtError::InvalidSignature)
+        );
+        assert!(!processor.verify_jwt(&tampered, SECRET));
+
+        // A valid signature under a different secret is also rejected
+        assert_eq!(
+            processor.verify_jwt_at(&token, b"other-secret", 1_700_000_100),
+            Err(JwtError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_verify_jwt_rejects_expired_token() {
+        let processor = CryptoProcessor::new();
+        let token = processor
+            .sign_jwt(&sample_payload(1_700_000_900), SECRET)
+            .unwrap();
+
+        let err = processor
+            .verify_jwt_at(&token, SECRET, 1_700_000_900)
+            .unwrap_err();
+        assert_eq!(err, JwtError::Expired);
+        assert!(err.to_string().contains("expired"), "{}", err);
+
+        // The signature is valid, but the token expired long before now
+        assert!(!processor.verify_jwt(&token, SECRET));
+    }
+
+    #[test]
+    fn test_verify_jwt_rejects_other_algorithms() {
+        let processor = CryptoProcessor::new();
+        let token = processor
+            .sign_jwt(&sample_payload(1_700_000_900), SECRET)
+            .unwrap();
+        let payload_b64 = token.split('.').nth(1).unwrap();
+
+        // Unsigned token claiming alg "none"
+        let none_header = base64_url_encode(br#"{"alg":"none","typ":"JWT"}"#);
+        let unsigned = format!("{}.{}.", none_header, payload_b64);
+        assert_eq!(
+            processor.verify_jwt_at(&unsigned, SECRET, 1_700_000_100),
+            Err(JwtError::UnsupportedAlgorithm("none".to_string()))
+        );
+        assert!(!processor.verify_jwt(&unsigned, SECRET));
+
+        // Correctly signed with the shared secret, but under a different header
+        let hs512_header = base64_url_encode(br#"{"alg":"HS512","typ":"JWT"}"#);
+        let message = format!("{}.{}", hs512_header, payload_b64);
+        let signature = processor.hmac_sha256(SECRET, message.as_bytes()).unwrap();
+        let resigned = format!("{}.{}", message, base64_url_encode(&signature));
+        assert_eq!(
+            processor.verify_jwt_at(&resigned, SECRET, 1_700_000_100),
+            Err(JwtError::UnsupportedAlgorithm("HS512".to_string()))
+        );
+
+        assert!(matches!(
+            processor.verify_jwt_at("not-a-jwt", SECRET, 1_700_000_100),
+            Err(JwtError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_base64url_segment_valid() {
+        let encoded = base64_url_encode(b"{\"sub\":\"user-1\"}?>");
+        assert!(encoded.contains('_') || encoded.contains('-'));
+        assert_eq!(
+            decode_base64url_segment(&encoded).unwrap(),
+            b"{\"sub\":\"user-1\"}?>"
+        );
+
+        // Padded input decodes to the same bytes
+        assert_eq!(decode_base64url_segment("YWI").unwrap(), b"ab");
+        assert_eq!(decode_base64url_segment("YWI=").unwrap(), b"ab");
+        assert_eq!(decode_base64url_segment("YQ==").unwrap(), b"a");
+    }
+
+    #[test]
+    fn test_decode_base64url_segment_illegal_characters() {
+        let err = decode_base64url_segment("YW+i").unwrap_err();
+        assert!(
+            err.contains("illegal character '+' at position 2"),
+            "{}",
+            err
+        );
+
+        let err = decode_base64url_segment("YW/i").unwrap_err();
+        assert!(err.contains("'/'"), "{}", err);
+
+        let err = decode_base64url_segment("YW i").unwrap_err();
+        assert!(err.contains("position 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_decode_base64url_segment_empty() {
+        let err = decode_base64url_segment("").unwrap_err();
+        assert!(err.contains("empty segment"), "{}", err);
+    }
+
+    #[test]
+    fn test_decode_base64url_round_trips_empty_input() {
+        assert_eq!(
+            decode_base64url(&base64_url_encode(&[])).unwrap(),
+            Vec::<u8>::new()
+        );
+        assert_eq!(decode_base64url("YWI").unwrap(), b"ab");
+        assert!(decode_base64url("Y").is_err());
+        assert!(decode_base64url_segment("").is_err());
+    }
+
+    #[test]
+    fn test_decode_base64url_segment_bad_length_and_padding() {
+        assert!(decode_base64url_segment("YWJjZ")
+            .unwrap_err()
+            .contains("length 5"));
+        assert!(decode_base64url_segment("YQ=")
+            .unwrap_err()
+            .contains("padding"));
+        assert!(decode_base64url_segment("Y===")
+            .unwrap_err()
+            .contains("padding"));
+        assert!(decode_base64url_segment("=")
+            .unwrap_err()
+            .contains("padding"));
+    }
+
+    #[test]
+    fn test_aes_gcm_round_trip() {
+        let processor = CryptoProcessor::new();
+        let key = [7u8; 32];
+        let plaintext = b"refresh-token-material";
+
+        let sealed = processor.aes_gcm_encrypt(plaintext, &key).unwrap();
+        assert_eq!(sealed.len(), 12 + plaintext.len() + 16);
+        assert_eq!(processor.aes_gcm_decrypt(&sealed, &key).unwrap(), plaintext);
+
+        // Fresh nonce per call
+        assert_ne!(processor.aes_gcm_encrypt(plaintext, &key).unwrap(), sealed);
+    }
+
+    #[test]
+    fn test_aes_gcm_rejects_tampering() {
+        let processor = CryptoProcessor::new();
+        let key = [7u8; 32];
+        let sealed = processor.aes_gcm_encrypt(b"secret", &key).unwrap();
+
+        for i in [0, 12, sealed.len() - 1] {
+            let mut tampered = sealed.clone();
+            tampered[i] ^= 0x01;
+            assert!(processor.aes_gcm_decrypt(&tampered, &key).is_err());
+        }
+
+        assert!(processor.aes_gcm_decrypt(&sealed, &[8u8; 32]).is_err());
+        assert!(processor.aes_gcm_decrypt(&sealed[..8], &key).is_err());
+    }
+
+    #[test]
+    fn test_aes_gcm_requires_32_byte_key() {
+        let processor = CryptoProcessor::new();
+
+        let err = processor
+            .aes_gcm_encrypt(b"secret", &[0u8; 16])
+            .unwrap_err();
+        assert!(err.contains("32 bytes"));
+        assert!(processor.aes_gcm_decrypt(&[0u8; 40], &[0u8; 31]).is_err());
+    }
+}
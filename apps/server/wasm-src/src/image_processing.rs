@@ -3,11 +3,12 @@ use std::ptr::NonNull;
 use std::alloc::{alloc, dealloc, Layout};
 use wasm_bindgen::prelude::*;
 use wasm_bindgen_futures::future_to_promise;
-use image::{ImageOutputFormat};
+use image::{DynamicImage, ImageFormat, ImageOutputFormat};
 use image::imageops::FilterType;
 use std::io::Cursor;
 use js_sys::Promise;
 use base64::{Engine as _, engine::general_purpose};
+use serde::Deserialize;
 
 pub struct MemoryPool {
     pools: HashMap<usize, Vec<NonNull<u8>>>,
@@ -60,7 +61,7 @@ impl MemoryPool {
             let size = layout.size();
             self.total_allocated -= size;
             
-            let pool = self.pools.entry(size).or_insert_with(Vec::new);
+            let pool = self.pools.entry(size).or_default();
             if pool.len() < 16 {
                 pool.push(ptr);
             } else {
@@ -177,6 +178,7 @@ pub struct WorkItem {
 pub struct WorkQueue {
     items: Vec<WorkItem>,
     next_id: usize,
+    #[allow(dead_code)]
     max_concurrent: usize,
 }
 
@@ -221,10 +223,142 @@ impl WorkQueue {
     }
 }
 
+// Mirrors the PhotoAdjustments GraphQL type carried in editingMetadata.
+// Every value is a -100..100 percentage; missing or null means no change.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct PhotoAdjustments {
+    pub brightness: Option<f32>,
+    pub contrast: Option<f32>,
+    pub saturation: Option<f32>,
+    pub exposure: Option<f32>,
+    pub shadows: Option<f32>,
+    pub highlights: Option<f32>,
+    pub temperature: Option<f32>,
+    pub tint: Option<f32>,
+}
+
+impl PhotoAdjustments {
+    // Named presets matching the filters offered in the mobile editor
+    pub fn filter_preset(name: &str) -> Option<PhotoAdjustments> {
+        let [brightness, contrast, saturation, exposure, shadows, highlights, temperature] =
+            match name.to_lowercase().as_str() {
+                "original" => [0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+                "vintage" => [10.0, -20.0, -30.0, -20.0, 20.0, -30.0, 40.0],
+                "noir" => [-10.0, 30.0, -80.0, -30.0, 30.0, -40.0, -20.0],
+                "vivid" => [20.0, 20.0, 40.0, 10.0, -10.0, 10.0, 10.0],
+                "cool" => [5.0, 10.0, 10.0, 0.0, -5.0, 10.0, -30.0],
+                "warm" => [10.0, 5.0, 20.0, 10.0, 10.0, 0.0, 40.0],
+                _ => return None,
+            };
+
+        Some(PhotoAdjustments {
+            brightness: Some(brightness),
+            contrast: Some(contrast),
+            saturation: Some(saturation),
+            exposure: Some(exposure),
+            shadows: Some(shadows),
+            highlights: Some(highlights),
+            temperature: Some(temperature),
+            tint: None,
+        })
+    }
+
+    pub fn scaled(&self, factor: f32) -> PhotoAdjustments {
+        let scale = |value: Option<f32>| value.map(|v| v * factor);
+        PhotoAdjustments {
+            brightness: scale(self.brightness),
+            contrast: scale(self.contrast),
+            saturation: scale(self.saturation),
+            exposure: scale(self.exposure),
+            shadows: scale(self.shadows),
+            highlights: scale(self.highlights),
+            temperature: scale(self.temperature),
+            tint: scale(self.tint),
+        }
+    }
+
+    // Applies exposure, brightness and contrast, then saturation, tone and
+    // white balance, clamping each channel to 0..255. Alpha is untouched.
+    pub fn apply_to(&self, image: &mut image::RgbaImage) {
+        let percent = |value: Option<f32>| value.unwrap_or(0.0) / 100.0;
+        let exposure = 2f32.powf(percent(self.exposure));
+        let brightness = percent(self.brightness) * 255.0;
+        let contrast = 1.0 + percent(self.contrast);
+        let saturation = 1.0 + percent(self.saturation);
+        let shadows = percent(self.shadows) * 64.0;
+        let highlights = percent(self.highlights) * 64.0;
+        let temperature = percent(self.temperature) * 32.0;
+        let tint = percent(self.tint) * 32.0;
+
+        for pixel in image.pixels_mut() {
+            let [r, g, b, a] = pixel.0;
+            let mut rgb = [r as f32, g as f32, b as f32];
+
+            for channel in rgb.iter_mut() {
+                *channel = (*channel * exposure + brightness - 128.0) * contrast + 128.0;
+            }
+
+            let luma = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+            for channel in rgb.iter_mut() {
+                *channel = luma + (*channel - luma) * saturation;
+            }
+
+            let tone = (luma / 255.0).clamp(0.0, 1.0);
+            let lift = shadows * (1.0 - tone).powi(2) + highlights * tone.powi(2);
+            rgb[0] += lift + temperature;
+            rgb[1] += lift - tint;
+            rgb[2] += lift - temperature;
+
+            pixel.0 = [
+                clamp_channel(rgb[0]),
+                clamp_channel(rgb[1]),
+                clamp_channel(rgb[2]),
+                a,
+            ];
+        }
+    }
+}
+
+fn clamp_channel(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+// Encoded image bytes together with their MIME type
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct ConvertedImage {
+    data: Vec<u8>,
+    mime_type: String,
+}
+
+#[wasm_bindgen]
+impl ConvertedImage {
+    #[wasm_bindgen(getter)]
+    pub fn data(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn mime_type(&self) -> String {
+        self.mime_type.clone()
+    }
+}
+
+impl ConvertedImage {
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+// Decoding larger images risks exhausting Worker memory
+const DEFAULT_MAX_PIXELS: u32 = 40_000_000;
+
 #[wasm_bindgen]
 pub struct ImageProcessor {
     memory_pool: MemoryPool,
     work_queue: WorkQueue,
+    max_pixels: u32,
 }
 
 #[wasm_bindgen]
@@ -235,6 +369,7 @@ impl ImageProcessor {
         Self {
             memory_pool: MemoryPool::new(max_pool_size),
             work_queue: WorkQueue::new(4), // Default to 4 concurrent operations
+            max_pixels: DEFAULT_MAX_PIXELS,
         }
     }
 
@@ -261,8 +396,7 @@ impl ImageProcessor {
 
     #[wasm_bindgen]
     pub fn resize(&mut self, image_data: &[u8], width: u32, height: u32, format: &str) -> Result<Vec<u8>, String> {
-        let img = image::load_from_memory(image_data)
-            .map_err(|e| format!("Failed to load image: {}", e))?;
+        let (img, _) = self.decode_checked(image_data)?;
         
         let resized = img.resize(width, height, FilterType::Lanczos3);
         
@@ -284,8 +418,7 @@ impl ImageProcessor {
 
     #[wasm_bindgen]
     pub fn convert_format(&mut self, image_data: &[u8], target_format: &str) -> Result<Vec<u8>, String> {
-        let img = image::load_from_memory(image_data)
-            .map_err(|e| format!("Failed to load image: {}", e))?;
+        let (img, _) = self.decode_checked(image_data)?;
         
         let mut output = Vec::new();
         let mut cursor = Cursor::new(&mut output);
@@ -309,8 +442,7 @@ impl ImageProcessor {
             return Err("Quality must be between 0 and 100".to_string());
         }
         
-        let img = image::load_from_memory(image_data)
-            .map_err(|e| format!("Failed to load image: {}", e))?;
+        let (img, _) = self.decode_checked(image_data)?;
         
         let mut output = Vec::new();
         let mut cursor = Cursor::new(&mut output);
@@ -330,15 +462,14 @@ impl ImageProcessor {
 
     #[wasm_bindgen]
     pub fn get_image_info(&self, image_data: &[u8]) -> Result<String, String> {
-        let img = image::load_from_memory(image_data)
-            .map_err(|e| format!("Failed to load image: {}", e))?;
+        let (img, _) = self.decode_checked(image_data)?;
         
         let info = format!(
-            "{{\"width\":{},\"height\":{},\"format\":\"{}\",\"color_type\":\"{}\"}}",
+            "{{\"width\":{},\"height\":{},\"format\":\"{}\",\"color_type\":\"{:?}\"}}",
             img.width(),
             img.height(),
             "unknown", // Would need to detect format from header
-            format!("{:?}", img.color())
+            img.color()
         );
         
         Ok(info)
@@ -438,12 +569,270 @@ impl ImageProcessor {
         self.work_queue.can_process()
     }
 
+    #[wasm_bindgen(getter)]
+    pub fn max_pixels(&self) -> u32 {
+        self.max_pixels
+    }
+
+    #[wasm_bindgen(setter)]
+    pub fn set_max_pixels(&mut self, max_pixels: u32) {
+        self.max_pixels = max_pixels;
+    }
+
+    // Scales down to fit within max_width x max_height, keeping the aspect ratio;
+    // smaller images are left at their size. Output uses the input format unless
+    // target_format is given.
+    #[wasm_bindgen]
+    pub fn resize_to_fit(
+        &self,
+        image_data: &[u8],
+        max_width: u32,
+        max_height: u32,
+        target_format: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        let (img, input_format) = self.decode_checked(image_data)?;
+
+        let fitted = if img.width() > max_width || img.height() > max_height {
+            img.resize(max_width, max_height, FilterType::Lanczos3)
+        } else {
+            img
+        };
+
+        encode_image(&fitted, input_format, target_format.as_deref())
+    }
+
+    // Square thumbnail: center-crops to the shorter side, then scales to `size`
+    #[wasm_bindgen]
+    pub fn generate_thumbnail(
+        &self,
+        image_data: &[u8],
+        size: u32,
+        target_format: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        if size == 0 {
+            return Err("Thumbnail size must be greater than 0".to_string());
+        }
+        let output_pixels = size as u64 * size as u64;
+        if output_pixels > self.max_pixels as u64 {
+            return Err(format!(
+                "Thumbnail of {} pixels exceeds the limit of {}",
+                output_pixels, self.max_pixels
+            ));
+        }
+
+        let (img, input_format) = self.decode_checked(image_data)?;
+
+        let side = img.width().min(img.height());
+        let x = (img.width() - side) / 2;
+        let y = (img.height() - side) / 2;
+        let thumbnail = img
+            .crop_imm(x, y, side, side)
+            .resize_exact(size, size, FilterType::Lanczos3);
+
+        encode_image(&thumbnail, input_format, target_format.as_deref())
+    }
+
+    // Targets "jpeg", "png", "webp" and "avif". Quality is clamped to 1..=100
+    // (default 85) and applies to JPEG and AVIF; PNG and WebP ignore it. The WebP
+    // encoder here is lossless, so WebP output of a photo is usually larger than
+    // the JPEG it replaces.
+    #[wasm_bindgen]
+    pub fn convert(&self, image_data: &[u8], target_format: &str, quality: Option<u8>) -> Result<ConvertedImage, String> {
+        let target = target_format.to_lowercase();
+        let quality = quality.unwrap_or(85).clamp(1, 100);
+
+        // AVIF has no ImageOutputFormat here; it is encoded by encode_avif
+        let (output_format, mime_type) = if target == "avif" {
+            (None, "image/avif")
+        } else {
+            let output_format = match output_format_from_name(&target)
+                .map_err(|_| format!("Unsupported target format: {}", target))?
+            {
+                ImageOutputFormat::Jpeg(_) => ImageOutputFormat::Jpeg(quality),
+                other => other,
+            };
+            let mime_type = match output_format {
+                ImageOutputFormat::Jpeg(_) => "image/jpeg",
+                ImageOutputFormat::Png => "image/png",
+                ImageOutputFormat::WebP => "image/webp",
+                ref other => return Err(format!("Unsupported target format: {:?}", other)),
+            };
+            (Some(output_format), mime_type)
+        };
+
+        let (img, _) = self.decode_checked(image_data)?;
+
+        let data = match output_format {
+            Some(output_format) => {
+                let mut data = Vec::new();
+                img.write_to(&mut Cursor::new(&mut data), output_format)
+                    .map_err(|e| format!("Failed to encode image as {}: {}", target, e))?;
+                data
+            }
+            None => encode_avif(&img, quality)?,
+        };
+
+        Ok(ConvertedImage {
+            data,
+            mime_type: mime_type.to_string(),
+        })
+    }
+
+    // Rotates/flips pixels so the EXIF orientation becomes 1, and re-encodes
+    // without any EXIF (including GPS). Images without EXIF are returned as-is.
+    #[wasm_bindgen]
+    pub fn normalize_orientation(&self, image_data: &[u8]) -> Result<Vec<u8>, String> {
+        let orientation = match read_exif_orientation(image_data) {
+            Some(orientation) => orientation,
+            None => return Ok(image_data.to_vec()),
+        };
+
+        let (img, input_format) = self.decode_checked(image_data)?;
+        encode_image(&apply_orientation(img, orientation), input_format, None)
+    }
+
+    // Takes the PhotoAdjustments JSON from editingMetadata
+    #[wasm_bindgen]
+    pub fn apply_adjustments(&self, image_data: &[u8], adjustments_json: &str) -> Result<Vec<u8>, String> {
+        let adjustments: PhotoAdjustments = serde_json::from_str(adjustments_json)
+            .map_err(|e| format!("Invalid adjustments: {}", e))?;
+
+        self.render_adjustments(image_data, &adjustments)
+    }
+
+    // Intensity runs from 0.0 (no change) to 1.0 (full preset)
+    #[wasm_bindgen]
+    pub fn apply_filter(&self, image_data: &[u8], filter_name: &str, intensity: f32) -> Result<Vec<u8>, String> {
+        if !intensity.is_finite() {
+            return Err("Filter intensity must be a finite number".to_string());
+        }
+
+        let preset = PhotoAdjustments::filter_preset(filter_name)
+            .ok_or_else(|| format!("Unknown filter: {}", filter_name))?;
+
+        self.render_adjustments(image_data, &preset.scaled(intensity.clamp(0.0, 1.0)))
+    }
 }
 
 impl ImageProcessor {
     pub fn create_buffer_internal(&mut self, width: u32, height: u32, channels: u8) -> Option<ImageBuffer> {
         ImageBuffer::new(width, height, channels, &mut self.memory_pool)
     }
+
+    // Reads the header first so oversized images are rejected before decoding
+    fn decode_checked(&self, image_data: &[u8]) -> Result<(DynamicImage, ImageFormat), String> {
+        let format = image::guess_format(image_data)
+            .map_err(|e| format!("Failed to detect image format: {}", e))?;
+
+        let (width, height) = image::io::Reader::with_format(Cursor::new(image_data), format)
+            .into_dimensions()
+            .map_err(|e| format!("Failed to read image dimensions: {}", e))?;
+
+        let pixels = width as u64 * height as u64;
+        if pixels > self.max_pixels as u64 {
+            return Err(format!(
+                "Image has {} pixels, exceeding the limit of {}",
+                pixels, self.max_pixels
+            ));
+        }
+
+        let img = image::load_from_memory_with_format(image_data, format)
+            .map_err(|e| format!("Failed to load image: {}", e))?;
+
+        Ok((img, format))
+    }
+
+    fn render_adjustments(&self, image_data: &[u8], adjustments: &PhotoAdjustments) -> Result<Vec<u8>, String> {
+        let (img, input_format) = self.decode_checked(image_data)?;
+        let has_alpha = img.color().has_alpha();
+
+        let mut rgba = img.to_rgba8();
+        adjustments.apply_to(&mut rgba);
+
+        let edited = if has_alpha {
+            DynamicImage::ImageRgba8(rgba)
+        } else {
+            DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(rgba).to_rgb8())
+        };
+
+        encode_image(&edited, input_format, None)
+    }
+}
+
+// None when the image carries no EXIF at all; unreadable EXIF or a missing
+// tag is treated as orientation 1 so the metadata still gets stripped
+fn read_exif_orientation(image_data: &[u8]) -> Option<u32> {
+    match exif::Reader::new().read_from_container(&mut Cursor::new(image_data)) {
+        Ok(exif) => Some(
+            exif.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                .and_then(|field| field.value.get_uint(0))
+                .unwrap_or(1),
+        ),
+        Err(exif::Error::NotFound(_)) => None,
+        Err(_) => Some(1),
+    }
+}
+
+fn apply_orientation(img: DynamicImage, orientation: u32) -> DynamicImage {
+    match orientation {
+        2 => img.fliph(),
+        3 => img.rotate180(),
+        4 => img.flipv(),
+        5 => img.rotate90().fliph(),
+        6 => img.rotate90(),
+        7 => img.rotate270().fliph(),
+        8 => img.rotate270(),
+        _ => img,
+    }
+}
+
+// Goes through ravif directly: image's avif-encoder feature enables rav1e's x86
+// assembly, which needs nasm at build time
+fn encode_avif(img: &DynamicImage, quality: u8) -> Result<Vec<u8>, String> {
+    let rgba = img.to_rgba8();
+    let pixels: Vec<ravif::RGBA8> = rgba
+        .pixels()
+        .map(|p| ravif::RGBA8::new(p[0], p[1], p[2], p[3]))
+        .collect();
+
+    let encoded = ravif::Encoder::new()
+        .with_quality(quality as f32)
+        .with_speed(8)
+        .encode_rgba(ravif::Img::new(&pixels[..], rgba.width() as usize, rgba.height() as usize))
+        .map_err(|e| format!("Failed to encode image as avif: {}", e))?;
+
+    Ok(encoded.avif_file)
+}
+
+fn output_format_from_name(format: &str) -> Result<ImageOutputFormat, String> {
+    match format.to_lowercase().as_str() {
+        "jpeg" | "jpg" => Ok(ImageOutputFormat::Jpeg(85)),
+        "png" => Ok(ImageOutputFormat::Png),
+        "webp" => Ok(ImageOutputFormat::WebP),
+        _ => Err("Unsupported format".to_string()),
+    }
+}
+
+fn encode_image(
+    img: &DynamicImage,
+    input_format: ImageFormat,
+    target_format: Option<&str>,
+) -> Result<Vec<u8>, String> {
+    let output_format = match target_format {
+        Some(format) => output_format_from_name(format)?,
+        None => match input_format {
+            ImageFormat::Jpeg => ImageOutputFormat::Jpeg(85),
+            ImageFormat::Png => ImageOutputFormat::Png,
+            ImageFormat::WebP => ImageOutputFormat::WebP,
+            other => return Err(format!("Unsupported input format: {:?}", other)),
+        },
+    };
+
+    let mut output = Vec::new();
+    img.write_to(&mut Cursor::new(&mut output), output_format)
+        .map_err(|e| format!("Failed to encode image: {}", e))?;
+
+    Ok(output)
 }
 
 #[cfg(test)]
@@ -569,4 +958,279 @@ mod tests {
         assert!(info.contains("width"));
         assert!(info.contains("height"));
     }
-}
\ No newline at end of file
+
+    fn fixture_png(width: u32, height: u32) -> Vec<u8> {
+        let img = ImageBufferLib::from_fn(width, height, |x, y| {
+            image::Rgb([(x * 4) as u8, (y * 4) as u8, 128u8])
+        });
+
+        let mut image_data = Vec::new();
+        DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut image_data), ImageOutputFormat::Png)
+            .unwrap();
+        image_data
+    }
+
+    #[test]
+    fn test_resize_to_fit_preserves_aspect_ratio() {
+        let processor = ImageProcessor::new(10);
+        let resized = processor
+            .resize_to_fit(&fixture_png(40, 20), 10, 10, None)
+            .unwrap();
+
+        assert_eq!(image::guess_format(&resized).unwrap(), ImageFormat::Png);
+        let img = image::load_from_memory(&resized).unwrap();
+        assert_eq!((img.width(), img.height()), (10, 5));
+    }
+
+    #[test]
+    fn test_resize_to_fit_never_upscales() {
+        let processor = ImageProcessor::new(10);
+        let resized = processor
+            .resize_to_fit(&fixture_png(40, 20), 100, 100, Some("jpeg".to_string()))
+            .unwrap();
+
+        assert_eq!(image::guess_format(&resized).unwrap(), ImageFormat::Jpeg);
+        let img = image::load_from_memory(&resized).unwrap();
+        assert_eq!((img.width(), img.height()), (40, 20));
+    }
+
+    #[test]
+    fn test_generate_thumbnail_is_square() {
+        let processor = ImageProcessor::new(10);
+        let thumbnail = processor
+            .generate_thumbnail(&fixture_png(40, 20), 8, None)
+            .unwrap();
+
+        let img = image::load_from_memory(&thumbnail).unwrap();
+        assert_eq!((img.width(), img.height()), (8, 8));
+        assert!(processor.generate_thumbnail(&fixture_png(4, 4), 0, None).is_err());
+    }
+
+    #[test]
+    fn test_generate_thumbnail_rejects_oversized_output() {
+        let mut processor = ImageProcessor::new(10);
+
+        let err = processor
+            .generate_thumbnail(&fixture_png(4, 4), 100_000, None)
+            .unwrap_err();
+        assert!(err.contains("exceeds the limit"));
+
+        processor.set_max_pixels(64);
+        assert!(processor.generate_thumbnail(&fixture_png(4, 4), 9, None).is_err());
+        assert!(processor.generate_thumbnail(&fixture_png(4, 4), 8, None).is_ok());
+    }
+
+    #[test]
+    fn test_pixel_limit_rejects_large_images() {
+        let mut processor = ImageProcessor::new(10);
+        processor.set_max_pixels(100);
+
+        let err = processor
+            .resize_to_fit(&fixture_png(40, 20), 10, 10, None)
+            .unwrap_err();
+        assert!(err.contains("exceeding the limit"));
+        assert!(processor.generate_thumbnail(&fixture_png(40, 20), 8, None).is_err());
+        assert!(processor.resize_to_fit(&fixture_png(10, 10), 5, 5, None).is_ok());
+    }
+
+    #[test]
+    fn test_pixel_limit_applies_to_legacy_operations() {
+        let mut processor = ImageProcessor::new(10);
+        processor.set_max_pixels(100);
+        let large = fixture_png(40, 20);
+
+        assert!(processor.resize(&large, 10, 10, "png").unwrap_err().contains("exceeding the limit"));
+        assert!(processor.convert_format(&large, "jpeg").unwrap_err().contains("exceeding the limit"));
+        assert!(processor.optimize_quality(&large, 80, "jpeg").unwrap_err().contains("exceeding the limit"));
+        assert!(processor.get_image_info(&large).unwrap_err().contains("exceeding the limit"));
+
+        processor.queue_resize(large, 10, 10, "png".to_string());
+        let result = processor.process_work_item().unwrap();
+        assert!(result.contains("\"status\":\"error\""), "{}", result);
+
+        assert!(processor.resize(&fixture_png(10, 10), 5, 5, "png").is_ok());
+    }
+
+    fn mean_luminance(image_data: &[u8]) -> f32 {
+        let img = image::load_from_memory(image_data).unwrap().to_luma8();
+        let total: u64 = img.pixels().map(|p| p.0[0] as u64).sum();
+        total as f32 / (img.width() * img.height()) as f32
+    }
+
+    #[test]
+    fn test_apply_adjustments_brightness_increases_luminance() {
+        let processor = ImageProcessor::new(10);
+        let original = fixture_png(16, 16);
+
+        let brightened = processor
+            .apply_adjustments(&original, r#"{"mediaId":"m1","brightness":50,"contrast":null}"#)
+            .unwrap();
+
+        assert!(mean_luminance(&brightened) > mean_luminance(&original));
+        assert!(processor.apply_adjustments(&original, "not json").is_err());
+    }
+
+    #[test]
+    fn test_adjustments_clamp_channels() {
+        let mut image = image::RgbaImage::from_pixel(2, 2, image::Rgba([250, 5, 128, 200]));
+        let adjustments = PhotoAdjustments {
+            brightness: Some(100.0),
+            contrast: Some(100.0),
+            ..PhotoAdjustments::default()
+        };
+
+        adjustments.apply_to(&mut image);
+        assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 200]);
+
+        let mut untouched = image::RgbaImage::from_pixel(1, 1, image::Rgba([10, 20, 30, 255]));
+        PhotoAdjustments::default().apply_to(&mut untouched);
+        assert_eq!(untouched.get_pixel(0, 0).0, [10, 20, 30, 255]);
+    }
+
+    #[test]
+    fn test_apply_filter() {
+        let processor = ImageProcessor::new(10);
+        let original = fixture_png(16, 16);
+
+        let noir = processor.apply_filter(&original, "Noir", 1.0).unwrap();
+        let img = image::load_from_memory(&noir).unwrap().to_rgb8();
+        let spread = |p: &image::Rgb<u8>| p.0.iter().max().unwrap() - p.0.iter().min().unwrap();
+        let original_img = image::load_from_memory(&original).unwrap().to_rgb8();
+        assert!(spread(img.get_pixel(15, 0)) < spread(original_img.get_pixel(15, 0)));
+
+        let unchanged = processor.apply_filter(&original, "vivid", 0.0).unwrap();
+        assert_eq!(image::load_from_memory(&unchanged).unwrap().to_rgb8(), original_img);
+
+        let err = processor.apply_filter(&original, "sepia", 1.0).unwrap_err();
+        assert!(err.contains("Unknown filter"));
+    }
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = 0xFFFF_FFFFu32;
+        for &byte in bytes {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
+
+    // 3x2 PNG with a red top-left marker and an eXIf chunk holding `orientation`
+    fn oriented_png(orientation: u16) -> Vec<u8> {
+        let img = ImageBufferLib::from_fn(3, 2, |x, y| {
+            if x == 0 && y == 0 {
+                image::Rgb([255u8, 0u8, 0u8])
+            } else {
+                image::Rgb([0u8, 0u8, 255u8])
+            }
+        });
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(img)
+            .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+            .unwrap();
+
+        // Big-endian TIFF header with a single Orientation (SHORT) entry
+        let mut tiff = b"MM\x00\x2a\x00\x00\x00\x08\x00\x01\x01\x12\x00\x03\x00\x00\x00\x01".to_vec();
+        tiff.extend_from_slice(&orientation.to_be_bytes());
+        tiff.extend_from_slice(&[0, 0, 0, 0, 0, 0]);
+
+        let mut chunk = b"eXIf".to_vec();
+        chunk.extend_from_slice(&tiff);
+        let mut encoded = (tiff.len() as u32).to_be_bytes().to_vec();
+        encoded.extend_from_slice(&chunk);
+        encoded.extend_from_slice(&crc32(&chunk).to_be_bytes());
+
+        // Insert right after the IHDR chunk
+        png.splice(33..33, encoded);
+        png
+    }
+
+    #[test]
+    fn test_normalize_orientation_all_values() {
+        let processor = ImageProcessor::new(10);
+        let expected = [
+            (1, (3, 2), (0, 0)),
+            (2, (3, 2), (2, 0)),
+            (3, (3, 2), (2, 1)),
+            (4, (3, 2), (0, 1)),
+            (5, (2, 3), (0, 0)),
+            (6, (2, 3), (1, 0)),
+            (7, (2, 3), (1, 2)),
+            (8, (2, 3), (0, 2)),
+        ];
+
+        for &(orientation, dimensions, (x, y)) in expected.iter() {
+            let input = oriented_png(orientation);
+            assert_eq!(read_exif_orientation(&input), Some(orientation as u32));
+
+            let output = processor.normalize_orientation(&input).unwrap();
+            let img = image::load_from_memory(&output).unwrap().to_rgb8();
+
+            assert_eq!(img.dimensions(), dimensions, "orientation {}", orientation);
+            assert_eq!(img.get_pixel(x, y).0, [255, 0, 0], "orientation {}", orientation);
+            assert_eq!(read_exif_orientation(&output), None);
+        }
+    }
+
+    #[test]
+    fn test_normalize_orientation_without_exif_is_unchanged() {
+        let processor = ImageProcessor::new(10);
+        let input = fixture_png(4, 4);
+
+        assert_eq!(processor.normalize_orientation(&input).unwrap(), input);
+    }
+
+    #[test]
+    fn test_convert_jpeg_to_webp() {
+        let processor = ImageProcessor::new(10);
+        let jpeg = processor
+            .convert(&fixture_png(8, 8), "jpeg", Some(90))
+            .unwrap()
+            .into_data();
+        assert_eq!(image::guess_format(&jpeg).unwrap(), ImageFormat::Jpeg);
+
+        let webp = processor.convert(&jpeg, "WebP", None).unwrap();
+        assert_eq!(webp.mime_type(), "image/webp");
+        assert_eq!(&webp.data()[0..4], b"RIFF");
+        assert_eq!(&webp.data()[8..12], b"WEBP");
+
+        // Lossless WebP ignores quality, like PNG
+        let with_quality = processor.convert(&jpeg, "webp", Some(80)).unwrap();
+        assert_eq!(with_quality.data(), webp.data());
+    }
+
+    #[test]
+    fn test_convert_to_avif() {
+        let processor = ImageProcessor::new(10);
+        let avif = processor
+            .convert(&fixture_png(16, 16), "avif", Some(60))
+            .unwrap();
+
+        assert_eq!(avif.mime_type(), "image/avif");
+        assert_eq!(&avif.data()[4..8], b"ftyp");
+        assert_eq!(&avif.data()[8..12], b"avif");
+    }
+
+    #[test]
+    fn test_convert_clamps_quality_and_reports_mime() {
+        let processor = ImageProcessor::new(10);
+        let input = fixture_png(8, 8);
+
+        assert_eq!(processor.convert(&input, "jpg", Some(0)).unwrap().mime_type(), "image/jpeg");
+        assert_eq!(processor.convert(&input, "png", Some(255)).unwrap().mime_type(), "image/png");
+        assert_eq!(processor.convert(&input, "avif", Some(255)).unwrap().mime_type(), "image/avif");
+    }
+
+    #[test]
+    fn test_convert_rejects_bad_input() {
+        let processor = ImageProcessor::new(10);
+        let input = fixture_png(8, 8);
+
+        assert!(processor.convert(&input, "gif", None).unwrap_err().contains("gif"));
+        assert!(processor.convert(&input[..20], "webp", None).is_err());
+        assert!(processor.convert(&input[..20], "avif", None).is_err());
+        assert!(processor.convert(b"not an image", "png", None).is_err());
+    }
+}
@@ -6,9 +6,11 @@ pub mod vector_math;
 use wasm_bindgen::prelude::*;
 
 // Re-export types for easier access
-pub use vector_math::{BatchProcessor, DiscoveryScorer, Vector1024};
+pub use vector_math::{
+    cosine_similarity, top_k_similar, BatchProcessor, DiscoveryScorer, Vector1024, VectorN,
+};
 pub use image_processing::ImageProcessor;
-pub use crypto::{CryptoProcessor, JwtPayload};
+pub use crypto::{CryptoProcessor, JwtError, JwtPayload};
 
 #[wasm_bindgen]
 extern "C" {
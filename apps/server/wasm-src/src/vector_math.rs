@@ -1,5 +1,8 @@
 use js_sys::Float32Array;
 use nalgebra::DVector;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cell::RefCell;
 use std::collections::HashMap;
 use wasm_bindgen::prelude::*;
 
@@ -9,6 +12,7 @@ pub enum VectorError {
     DimensionMismatch,
     InvalidInput,
     ComputationError,
+    NonFiniteVector(usize),
 }
 
 impl std::fmt::Display for VectorError {
@@ -17,18 +21,185 @@ impl std::fmt::Display for VectorError {
             VectorError::DimensionMismatch => write!(f, "Vector dimensions do not match"),
             VectorError::InvalidInput => write!(f, "Invalid input provided"),
             VectorError::ComputationError => write!(f, "Error during computation"),
+            VectorError::NonFiniteVector(index) => {
+                write!(f, "Vector {} contains NaN or infinite values", index)
+            }
         }
     }
 }
 
 pub type VectorResult<T> = Result<T, VectorError>;
 
-// 1024-dimensional vector operations for Voyage embeddings
+// Variable-dimension vector; operations between two vectors error when their
+// dimensions differ
 #[wasm_bindgen]
-pub struct Vector1024 {
+#[derive(Clone)]
+pub struct VectorN {
     data: DVector<f32>,
 }
 
+#[wasm_bindgen]
+impl VectorN {
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[f32]) -> Result<VectorN, JsValue> {
+        VectorN::from_slice(data).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn with_dimension(data: &[f32], dimension: usize) -> Result<VectorN, JsValue> {
+        if data.len() != dimension {
+            return Err(JsValue::from_str(&format!(
+                "Vector must be exactly {} dimensions",
+                dimension
+            )));
+        }
+        VectorN::new(data)
+    }
+
+    #[wasm_bindgen]
+    pub fn from_js_array(data: &Float32Array) -> Result<VectorN, JsValue> {
+        let vec_data: Vec<f32> = data.to_vec();
+        VectorN::new(&vec_data)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dimension(&self) -> usize {
+        self.data.len()
+    }
+
+    #[wasm_bindgen]
+    pub fn dot_product(&self, other: &VectorN) -> Result<f32, JsValue> {
+        self.try_dot_product(other).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn magnitude(&self) -> f32 {
+        self.data.norm()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_normalized(&self, tol: f32) -> bool {
+        (self.magnitude() - 1.0).abs() <= tol
+    }
+
+    // Zero vectors are kept as zeros rather than dividing by a zero magnitude
+    #[wasm_bindgen]
+    pub fn normalize(&self) -> VectorN {
+        VectorN {
+            data: self
+                .data
+                .try_normalize(0.0)
+                .unwrap_or_else(|| self.data.clone()),
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn cosine_similarity(&self, other: &VectorN) -> Result<f32, JsValue> {
+        self.try_cosine_similarity(other).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn euclidean_distance(&self, other: &VectorN) -> Result<f32, JsValue> {
+        self.try_euclidean_distance(other).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn manhattan_distance(&self, other: &VectorN) -> Result<f32, JsValue> {
+        self.try_manhattan_distance(other).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn to_js_array(&self) -> Float32Array {
+        Float32Array::from(self.as_slice())
+    }
+
+    #[wasm_bindgen]
+    pub fn scale(&self, factor: f32) -> VectorN {
+        VectorN {
+            data: &self.data * factor,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn add(&self, other: &VectorN) -> Result<VectorN, JsValue> {
+        self.try_add(other).map_err(to_js_error)
+    }
+
+    #[wasm_bindgen]
+    pub fn subtract(&self, other: &VectorN) -> Result<VectorN, JsValue> {
+        self.try_subtract(other).map_err(to_js_error)
+    }
+}
+
+impl VectorN {
+    pub fn from_slice(data: &[f32]) -> VectorResult<VectorN> {
+        if data.is_empty() {
+            return Err(VectorError::InvalidInput);
+        }
+
+        Ok(VectorN {
+            data: DVector::from_column_slice(data),
+        })
+    }
+
+    pub fn as_slice(&self) -> &[f32] {
+        self.data.as_slice()
+    }
+
+    fn check_dimension(&self, other: &VectorN) -> VectorResult<()> {
+        if self.data.len() != other.data.len() {
+            return Err(VectorError::DimensionMismatch);
+        }
+        Ok(())
+    }
+
+    pub fn try_dot_product(&self, other: &VectorN) -> VectorResult<f32> {
+        self.check_dimension(other)?;
+        Ok(self.data.dot(&other.data))
+    }
+
+    pub fn try_cosine_similarity(&self, other: &VectorN) -> VectorResult<f32> {
+        self.check_dimension(other)?;
+        Ok(cosine_similarity(self.as_slice(), other.as_slice()))
+    }
+
+    pub fn try_euclidean_distance(&self, other: &VectorN) -> VectorResult<f32> {
+        self.check_dimension(other)?;
+        Ok((&self.data - &other.data).norm())
+    }
+
+    pub fn try_manhattan_distance(&self, other: &VectorN) -> VectorResult<f32> {
+        self.check_dimension(other)?;
+        Ok((&self.data - &other.data).iter().map(|x| x.abs()).sum())
+    }
+
+    pub fn try_add(&self, other: &VectorN) -> VectorResult<VectorN> {
+        self.check_dimension(other)?;
+        Ok(VectorN {
+            data: &self.data + &other.data,
+        })
+    }
+
+    pub fn try_subtract(&self, other: &VectorN) -> VectorResult<VectorN> {
+        self.check_dimension(other)?;
+        Ok(VectorN {
+            data: &self.data - &other.data,
+        })
+    }
+}
+
+fn to_js_error(e: VectorError) -> JsValue {
+    JsValue::from_str(&e.to_string())
+}
+
+// 1024-dimensional vector operations for Voyage embeddings, kept as a
+// fixed-size wrapper over VectorN for existing callers
+#[wasm_bindgen]
+#[derive(Clone)]
+pub struct Vector1024 {
+    inner: VectorN,
+}
+
 #[wasm_bindgen]
 impl Vector1024 {
     #[wasm_bindgen(constructor)]
@@ -37,9 +208,17 @@ impl Vector1024 {
             return Err(JsValue::from_str("Vector must be exactly 1024 dimensions"));
         }
 
-        Ok(Vector1024 {
-            data: DVector::from_column_slice(data),
-        })
+        Ok(Vector1024::from_dvector(DVector::from_column_slice(data)))
+    }
+
+    // Normalizes on construction; zero vectors are kept as zeros
+    #[wasm_bindgen]
+    pub fn new_normalized(data: &[f32]) -> Result<Vector1024, JsValue> {
+        let vector = Vector1024::new(data)?;
+        if vector.magnitude() == 0.0 {
+            return Ok(vector);
+        }
+        Ok(vector.normalize())
     }
 
     #[wasm_bindgen]
@@ -49,75 +228,88 @@ impl Vector1024 {
         }
 
         let vec_data: Vec<f32> = data.to_vec();
-        Ok(Vector1024 {
-            data: DVector::from_column_slice(&vec_data),
-        })
+        Vector1024::new(&vec_data)
+    }
+
+    #[wasm_bindgen]
+    pub fn to_vector_n(&self) -> VectorN {
+        self.inner.clone()
     }
 
+    // Equals cosine similarity only when both vectors are normalized
     #[wasm_bindgen]
     pub fn dot_product(&self, other: &Vector1024) -> f32 {
-        self.data.dot(&other.data)
+        self.inner.data.dot(&other.inner.data)
     }
 
     #[wasm_bindgen]
     pub fn magnitude(&self) -> f32 {
-        self.data.norm()
+        self.inner.magnitude()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_normalized(&self, tol: f32) -> bool {
+        self.inner.is_normalized(tol)
     }
 
     #[wasm_bindgen]
     pub fn normalize(&self) -> Vector1024 {
-        let normalized = self.data.normalize();
-        Vector1024 { data: normalized }
+        Vector1024 {
+            inner: self.inner.normalize(),
+        }
     }
 
     #[wasm_bindgen]
     pub fn cosine_similarity(&self, other: &Vector1024) -> f32 {
-        let dot = self.dot_product(other);
-        let mag_product = self.magnitude() * other.magnitude();
-
-        if mag_product == 0.0 {
-            0.0
-        } else {
-            dot / mag_product
-        }
+        cosine_similarity(self.as_slice(), other.as_slice())
     }
 
     #[wasm_bindgen]
     pub fn euclidean_distance(&self, other: &Vector1024) -> f32 {
-        (&self.data - &other.data).norm()
+        (&self.inner.data - &other.inner.data).norm()
     }
 
     #[wasm_bindgen]
     pub fn manhattan_distance(&self, other: &Vector1024) -> f32 {
-        (&self.data - &other.data).iter().map(|x| x.abs()).sum()
+        (&self.inner.data - &other.inner.data)
+            .iter()
+            .map(|x| x.abs())
+            .sum()
     }
 
     #[wasm_bindgen]
     pub fn to_js_array(&self) -> Float32Array {
-        let data: Vec<f32> = self.data.iter().cloned().collect();
-        Float32Array::from(&data[..])
+        self.inner.to_js_array()
     }
 
     #[wasm_bindgen]
     pub fn scale(&self, factor: f32) -> Vector1024 {
         Vector1024 {
-            data: &self.data * factor,
+            inner: self.inner.scale(factor),
         }
     }
 
     #[wasm_bindgen]
     pub fn add(&self, other: &Vector1024) -> Vector1024 {
-        Vector1024 {
-            data: &self.data + &other.data,
-        }
+        Vector1024::from_dvector(&self.inner.data + &other.inner.data)
     }
 
     #[wasm_bindgen]
     pub fn subtract(&self, other: &Vector1024) -> Vector1024 {
+        Vector1024::from_dvector(&self.inner.data - &other.inner.data)
+    }
+}
+
+impl Vector1024 {
+    fn from_dvector(data: DVector<f32>) -> Vector1024 {
         Vector1024 {
-            data: &self.data - &other.data,
+            inner: VectorN { data },
         }
     }
+
+    pub fn as_slice(&self) -> &[f32] {
+        self.inner.as_slice()
+    }
 }
 
 // Discovery feed scoring engine
@@ -125,6 +317,9 @@ impl Vector1024 {
 pub struct DiscoveryScorer {
     user_preferences: Vector1024,
     content_weights: HashMap<String, f32>,
+    // Probability of replacing a score with a random exploration score
+    epsilon: f32,
+    rng: RefCell<StdRng>,
 }
 
 #[wasm_bindgen]
@@ -138,13 +333,29 @@ impl DiscoveryScorer {
         content_weights.insert("diversity".to_string(), 0.1);
 
         DiscoveryScorer {
-            user_preferences: Vector1024 {
-                data: user_preferences.data.clone(),
-            },
+            user_preferences: user_preferences.clone(),
             content_weights,
+            epsilon: 0.0,
+            rng: RefCell::new(StdRng::from_entropy()),
         }
     }
 
+    #[wasm_bindgen]
+    pub fn set_epsilon(&mut self, epsilon: f32) {
+        self.epsilon = epsilon.clamp(0.0, 1.0);
+    }
+
+    #[wasm_bindgen]
+    pub fn epsilon(&self) -> f32 {
+        self.epsilon
+    }
+
+    // Reseed the exploration RNG so exploration scores are reproducible
+    #[wasm_bindgen]
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = RefCell::new(StdRng::seed_from_u64(seed));
+    }
+
     #[wasm_bindgen]
     pub fn score_content(
         &self,
@@ -164,6 +375,14 @@ impl DiscoveryScorer {
             + popularity_score * self.content_weights.get("popularity").unwrap_or(&0.2)
             + similarity_penalty * self.content_weights.get("diversity").unwrap_or(&0.1);
 
+        // Epsilon-greedy exploration: occasionally let less-similar content rank high
+        if self.epsilon > 0.0 {
+            let mut rng = self.rng.borrow_mut();
+            if rng.gen::<f32>() < self.epsilon {
+                return rng.gen::<f32>();
+            }
+        }
+
         final_score.clamp(0.0, 1.0)
     }
 
@@ -187,113 +406,359 @@ impl DiscoveryScorer {
                 .insert("diversity".to_string(), diversity / total);
         }
     }
+
+    // Presets: "chronological" (recency dominates), "relevance", or "balanced" (the default)
+    #[wasm_bindgen]
+    pub fn with_preset(
+        user_preferences: &Vector1024,
+        preset: &str,
+    ) -> Result<DiscoveryScorer, JsValue> {
+        let [relevance, recency, popularity, diversity] = preset_weights(preset)
+            .ok_or_else(|| JsValue::from_str(&format!("Unknown scoring preset: {}", preset)))?;
+
+        let mut scorer = DiscoveryScorer::new(user_preferences);
+        scorer.update_weights(relevance, recency, popularity, diversity);
+        Ok(scorer)
+    }
+
+    #[wasm_bindgen]
+    pub fn weights(&self) -> js_sys::Object {
+        let weights = js_sys::Object::new();
+        for factor in ["relevance", "recency", "popularity", "diversity"] {
+            let _ = js_sys::Reflect::set(
+                &weights,
+                &JsValue::from_str(factor),
+                &JsValue::from(self.weight(factor)),
+            );
+        }
+        weights
+    }
+
+    // Maximal Marginal Relevance: picks up to k candidates in order, trading
+    // relevance against similarity to items already picked. lambda = 1.0 is
+    // pure relevance ordering.
+    #[wasm_bindgen]
+    pub fn rerank_mmr(
+        &self,
+        candidates: &Float32Array,
+        lambda: f32,
+        k: usize,
+    ) -> js_sys::Uint32Array {
+        let data: Vec<f32> = candidates.to_vec();
+        let selected: Vec<u32> = self
+            .mmr_order(&data, lambda, k)
+            .into_iter()
+            .map(|idx| idx as u32)
+            .collect();
+        js_sys::Uint32Array::from(&selected[..])
+    }
+}
+
+impl DiscoveryScorer {
+    pub fn weight(&self, factor: &str) -> f32 {
+        self.content_weights.get(factor).copied().unwrap_or(0.0)
+    }
+
+    // Scores consecutive 1024-d content vectors; items missing a recency or
+    // popularity score get 0.0
+    pub fn score_batch(
+        &self,
+        content_data: &[f32],
+        recency_scores: &[f32],
+        popularity_scores: &[f32],
+    ) -> Vec<f32> {
+        content_data
+            .chunks_exact(1024)
+            .enumerate()
+            .map(
+                |(i, vector_slice)| match (recency_scores.get(i), popularity_scores.get(i)) {
+                    (Some(&recency), Some(&popularity)) => {
+                        let content_vector =
+                            Vector1024::from_dvector(DVector::from_column_slice(vector_slice));
+                        self.score_content(&content_vector, recency, popularity)
+                    }
+                    _ => 0.0,
+                },
+            )
+            .collect()
+    }
+
+    pub fn mmr_order(&self, content_data: &[f32], lambda: f32, k: usize) -> Vec<usize> {
+        let lambda = lambda.clamp(0.0, 1.0);
+        let vectors: Vec<&[f32]> = content_data.chunks_exact(1024).collect();
+        let relevance: Vec<f32> = vectors
+            .iter()
+            .map(|v| cosine_similarity(self.user_preferences.as_slice(), v))
+            .collect();
+
+        let mut remaining: Vec<usize> = (0..vectors.len()).collect();
+        let mut selected: Vec<usize> = Vec::with_capacity(k.min(vectors.len()));
+
+        while selected.len() < k && !remaining.is_empty() {
+            let mut best: Option<(usize, f32)> = None;
+            for (pos, &candidate) in remaining.iter().enumerate() {
+                // Max similarity to what is already picked; negative when the
+                // candidate points away from every selected item
+                let redundancy = if selected.is_empty() {
+                    0.0
+                } else {
+                    selected
+                        .iter()
+                        .map(|&chosen| cosine_similarity(vectors[candidate], vectors[chosen]))
+                        .fold(f32::NEG_INFINITY, f32::max)
+                };
+                let score = lambda * relevance[candidate] - (1.0 - lambda) * redundancy;
+
+                let better = match best {
+                    None => true,
+                    Some((_, best_score)) => {
+                        compare_scores_desc(score, best_score) == std::cmp::Ordering::Less
+                    }
+                };
+                if better {
+                    best = Some((pos, score));
+                }
+            }
+
+            if let Some((pos, _)) = best {
+                selected.push(remaining.remove(pos));
+            }
+        }
+
+        selected
+    }
+}
+
+// Relevance, recency, popularity, diversity
+fn preset_weights(preset: &str) -> Option<[f32; 4]> {
+    match preset {
+        "chronological" => Some([0.1, 0.7, 0.1, 0.1]),
+        "relevance" => Some([0.7, 0.1, 0.1, 0.1]),
+        "balanced" => Some([0.4, 0.3, 0.2, 0.1]),
+        _ => None,
+    }
 }
 
-// Batch processing for user preference vectors
+// Batch processing for user preference vectors. Candidate vectors are packed
+// back to back in one array, `dimension` values each (1024 by default).
+// Similarity methods divide by magnitudes and accept raw vectors; `compute_centroid`
+// averages raw values, so pass normalized vectors when only direction should count.
 #[wasm_bindgen]
 pub struct BatchProcessor {
+    #[allow(dead_code)]
     batch_size: usize,
+    dimension: usize,
 }
 
 #[wasm_bindgen]
 impl BatchProcessor {
     #[wasm_bindgen(constructor)]
-    pub fn new(batch_size: usize) -> BatchProcessor {
-        BatchProcessor { batch_size }
+    pub fn new(batch_size: usize, dimension: Option<usize>) -> Result<BatchProcessor, JsValue> {
+        BatchProcessor::with_dimension(batch_size, dimension.unwrap_or(1024))
+            .map_err(|_| JsValue::from_str("Vector dimension must be greater than 0"))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn dimension(&self) -> usize {
+        self.dimension
     }
 
     #[wasm_bindgen]
     pub fn process_similarity_batch(
         &self,
-        query_vector: &Vector1024,
+        query_vector: &VectorN,
         vectors_data: &Float32Array,
-    ) -> Float32Array {
-        let vectors_len = vectors_data.length() as usize;
-        let num_vectors = vectors_len / 1024;
-        let mut similarities = Vec::with_capacity(num_vectors);
-
+    ) -> Result<Float32Array, JsValue> {
         let data: Vec<f32> = vectors_data.to_vec();
-
-        for i in 0..num_vectors {
-            let start_idx = i * 1024;
-            let end_idx = start_idx + 1024;
-
-            if end_idx <= data.len() {
-                let vector_slice = &data[start_idx..end_idx];
-                if let Ok(vector) = Vector1024::new(vector_slice) {
-                    let similarity = query_vector.cosine_similarity(&vector);
-                    similarities.push(similarity);
-                } else {
-                    similarities.push(0.0);
-                }
-            } else {
-                similarities.push(0.0);
-            }
-        }
-
-        Float32Array::from(&similarities[..])
+        let similarities = self
+            .similarity_scores(query_vector.as_slice(), &data)
+            .map_err(to_js_error)?;
+        Ok(Float32Array::from(&similarities[..]))
     }
 
     #[wasm_bindgen]
     pub fn find_top_k_similar(
         &self,
-        query_vector: &Vector1024,
+        query_vector: &VectorN,
         vectors_data: &Float32Array,
         k: usize,
-    ) -> Float32Array {
-        let similarities = self.process_similarity_batch(query_vector, vectors_data);
-        let mut similarity_vec: Vec<(f32, usize)> = similarities
-            .to_vec()
+    ) -> Result<Float32Array, JsValue> {
+        let data: Vec<f32> = vectors_data.to_vec();
+        let top_k: Vec<f32> = self
+            .top_k_indexed(query_vector.as_slice(), &data, k)
+            .map_err(to_js_error)?
             .iter()
-            .enumerate()
-            .map(|(idx, &sim)| (sim, idx))
+            .map(|(_, sim)| *sim)
             .collect();
 
-        // Sort by similarity (descending)
-        similarity_vec.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        Ok(Float32Array::from(&top_k[..]))
+    }
 
-        // Take top k
-        let top_k: Vec<f32> = similarity_vec
-            .iter()
-            .take(k.min(similarity_vec.len()))
-            .map(|(sim, _)| *sim)
-            .collect();
+    // Returns `[index, score]` pairs sorted by descending similarity
+    #[wasm_bindgen]
+    pub fn find_top_k_similar_indexed(
+        &self,
+        query_vector: &VectorN,
+        vectors_data: &Float32Array,
+        k: usize,
+    ) -> Result<js_sys::Array, JsValue> {
+        let data: Vec<f32> = vectors_data.to_vec();
+        let top_k = self
+            .top_k_indexed(query_vector.as_slice(), &data, k)
+            .map_err(to_js_error)?;
 
-        Float32Array::from(&top_k[..])
+        Ok(indexed_scores_to_js(&top_k))
     }
 
     #[wasm_bindgen]
-    pub fn compute_centroid(&self, vectors_data: &Float32Array) -> Result<Vector1024, JsValue> {
-        let vectors_len = vectors_data.length() as usize;
-        let num_vectors = vectors_len / 1024;
+    pub fn compute_centroid(&self, vectors_data: &Float32Array) -> Result<VectorN, JsValue> {
+        let data: Vec<f32> = vectors_data.to_vec();
+        self.centroid(&data)
+            .map_err(|_| JsValue::from_str("No vectors provided"))
+    }
 
-        if num_vectors == 0 {
-            return Err(JsValue::from_str("No vectors provided"));
+    #[wasm_bindgen]
+    pub fn compute_weighted_centroid(
+        &self,
+        vectors_data: &Float32Array,
+        weights: &[f32],
+    ) -> Result<VectorN, JsValue> {
+        let data: Vec<f32> = vectors_data.to_vec();
+        self.weighted_centroid(&data, weights).map_err(|e| match e {
+            VectorError::DimensionMismatch => {
+                JsValue::from_str("Weight count must match vector count")
+            }
+            VectorError::InvalidInput => JsValue::from_str(
+                "Vectors and weights must be non-empty with non-zero total weight",
+            ),
+            e => to_js_error(e),
+        })
+    }
+}
+
+impl BatchProcessor {
+    pub fn with_dimension(batch_size: usize, dimension: usize) -> VectorResult<BatchProcessor> {
+        if dimension == 0 {
+            return Err(VectorError::InvalidInput);
         }
 
-        let data: Vec<f32> = vectors_data.to_vec();
-        let mut centroid = vec![0.0f32; 1024];
+        Ok(BatchProcessor {
+            batch_size,
+            dimension,
+        })
+    }
 
-        for i in 0..num_vectors {
-            let start_idx = i * 1024;
-            let end_idx = start_idx + 1024;
+    pub fn similarity_scores(&self, query: &[f32], data: &[f32]) -> VectorResult<Vec<f32>> {
+        if query.len() != self.dimension {
+            return Err(VectorError::DimensionMismatch);
+        }
 
-            if end_idx <= data.len() {
-                for (j, &value) in data[start_idx..end_idx].iter().enumerate() {
-                    centroid[j] += value;
-                }
+        Ok(data
+            .chunks_exact(self.dimension)
+            .map(|vector| cosine_similarity(query, vector))
+            .collect())
+    }
+
+    pub fn top_k_indexed(
+        &self,
+        query: &[f32],
+        data: &[f32],
+        k: usize,
+    ) -> VectorResult<Vec<(usize, f32)>> {
+        if query.len() != self.dimension {
+            return Err(VectorError::DimensionMismatch);
+        }
+
+        let candidates: Vec<&[f32]> = data.chunks_exact(self.dimension).collect();
+        Ok(top_k_similar(query, &candidates, k))
+    }
+
+    pub fn centroid(&self, data: &[f32]) -> VectorResult<VectorN> {
+        let num_vectors = data.len() / self.dimension;
+        self.weighted_centroid(data, &vec![1.0; num_vectors])
+    }
+
+    // Weight-normalized mean of the vectors in `data`
+    pub fn weighted_centroid(&self, data: &[f32], weights: &[f32]) -> VectorResult<VectorN> {
+        let num_vectors = data.len() / self.dimension;
+
+        if num_vectors == 0 {
+            return Err(VectorError::InvalidInput);
+        }
+        if weights.len() != num_vectors {
+            return Err(VectorError::DimensionMismatch);
+        }
+
+        let total_weight: f32 = weights.iter().sum();
+        if total_weight == 0.0 || !total_weight.is_finite() {
+            return Err(VectorError::InvalidInput);
+        }
+
+        let mut centroid = vec![0.0f32; self.dimension];
+        for (vector, &weight) in data.chunks_exact(self.dimension).zip(weights.iter()) {
+            for (j, &value) in vector.iter().enumerate() {
+                centroid[j] += value * weight;
             }
         }
 
-        // Average
         for value in centroid.iter_mut() {
-            *value /= num_vectors as f32;
+            *value /= total_weight;
         }
 
-        Vector1024::new(&centroid)
+        Ok(VectorN {
+            data: DVector::from_vec(centroid),
+        })
     }
 }
 
+fn indexed_scores_to_js(scores: &[(usize, f32)]) -> js_sys::Array {
+    let result = js_sys::Array::new();
+    for &(idx, score) in scores {
+        let pair = js_sys::Array::new();
+        pair.push(&JsValue::from(idx as u32));
+        pair.push(&JsValue::from(score));
+        result.push(&pair);
+    }
+    result
+}
+
+// Plain-Rust similarity core shared by the WASM entry points.
+// Returns 0.0 for mismatched lengths or zero-magnitude inputs; NaN elements propagate.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+
+    let dot_product: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot_product / (norm_a * norm_b)
+    }
+}
+
+// Returns `(index, similarity)` pairs for the k most similar candidates, best first
+pub fn top_k_similar<V: AsRef<[f32]>>(
+    query: &[f32],
+    candidates: &[V],
+    k: usize,
+) -> Vec<(usize, f32)> {
+    let mut similarity_vec: Vec<(usize, f32)> = candidates
+        .iter()
+        .map(|candidate| cosine_similarity(query, candidate.as_ref()))
+        .enumerate()
+        .collect();
+
+    // Sort by similarity (descending)
+    similarity_vec.sort_by(|a, b| compare_scores_desc(a.1, b.1));
+
+    similarity_vec.truncate(k);
+    similarity_vec
+}
+
 // High-level convenience functions
 #[wasm_bindgen]
 pub fn compute_batch_similarities(
@@ -301,8 +766,12 @@ pub fn compute_batch_similarities(
     vectors_data: &Float32Array,
 ) -> Result<Float32Array, JsValue> {
     let query_vector = Vector1024::new(query_data)?;
-    let processor = BatchProcessor::new(100);
-    Ok(processor.process_similarity_batch(&query_vector, vectors_data))
+    let processor = BatchProcessor::new(100, None)?;
+    let data: Vec<f32> = vectors_data.to_vec();
+    let similarities = processor
+        .similarity_scores(query_vector.as_slice(), &data)
+        .map_err(to_js_error)?;
+    Ok(Float32Array::from(&similarities[..]))
 }
 
 #[wasm_bindgen]
@@ -312,8 +781,8 @@ pub fn find_most_similar_vectors(
     k: usize,
 ) -> Result<Float32Array, JsValue> {
     let query_vector = Vector1024::new(query_data)?;
-    let processor = BatchProcessor::new(100);
-    Ok(processor.find_top_k_similar(&query_vector, vectors_data, k))
+    let processor = BatchProcessor::new(100, None)?;
+    processor.find_top_k_similar(&query_vector.to_vector_n(), vectors_data, k)
 }
 
 #[wasm_bindgen]
@@ -325,53 +794,50 @@ pub fn score_content_batch(
 ) -> Result<Float32Array, JsValue> {
     let user_vector = Vector1024::new(user_prefs)?;
     let scorer = DiscoveryScorer::new(&user_vector);
-
-    let vectors_len = content_vectors.length() as usize;
-    let num_vectors = vectors_len / 768;
     let content_data: Vec<f32> = content_vectors.to_vec();
 
-    let mut scores = Vec::with_capacity(num_vectors);
-
-    for i in 0..num_vectors {
-        let start_idx = i * 768;
-        let end_idx = start_idx + 768;
-
-        if end_idx <= content_data.len() && i < recency_scores.len() && i < popularity_scores.len()
-        {
-            let vector_slice = &content_data[start_idx..end_idx];
-            if let Ok(content_vector) = Vector1024::new(vector_slice) {
-                let score =
-                    scorer.score_content(&content_vector, recency_scores[i], popularity_scores[i]);
-                scores.push(score);
-            } else {
-                scores.push(0.0);
-            }
-        } else {
-            scores.push(0.0);
-        }
-    }
-
+    let scores = scorer.score_batch(&content_data, recency_scores, popularity_scores);
     Ok(Float32Array::from(&scores[..]))
 }
 
-// Enhanced vector operations for optimization
+// Enhanced vector operations for optimization.
+// The batch helpers below compute full cosine similarity and do not require
+// normalized inputs; pre-normalizing only saves work for dot-product callers.
+// Zero-magnitude vectors are left as zeros. Vectors containing NaN or infinite
+// elements are an error unless `skip_invalid` is set, in which case they are zero-filled.
 #[wasm_bindgen]
-pub fn batch_normalize_vectors(vectors: &Float32Array) -> Result<Float32Array, JsValue> {
+pub fn batch_normalize_vectors(
+    vectors: &Float32Array,
+    skip_invalid: Option<bool>,
+) -> Result<Float32Array, JsValue> {
     let mut data: Vec<f32> = vectors.to_vec();
-    let vector_len = 1024;
-    let num_vectors = data.len() / vector_len;
 
-    if data.len() % vector_len != 0 {
+    if data.len() % 1024 != 0 {
         return Err(JsValue::from_str(
             "Vector data length must be divisible by 1024",
         ));
     }
 
+    normalize_vectors_in_place(&mut data, skip_invalid.unwrap_or(false))
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    Ok(Float32Array::from(&data[..]))
+}
+
+pub fn normalize_vectors_in_place(data: &mut [f32], skip_invalid: bool) -> VectorResult<()> {
+    if data.len() % 1024 != 0 {
+        return Err(VectorError::DimensionMismatch);
+    }
+
     // Process each vector
-    for i in 0..num_vectors {
-        let start_idx = i * vector_len;
-        let end_idx = start_idx + vector_len;
-        let vector_slice = &mut data[start_idx..end_idx];
+    for (index, vector_slice) in data.chunks_exact_mut(1024).enumerate() {
+        if vector_slice.iter().any(|x| !x.is_finite()) {
+            if skip_invalid {
+                vector_slice.fill(0.0);
+                continue;
+            }
+            return Err(VectorError::NonFiniteVector(index));
+        }
 
         // Calculate magnitude
         let magnitude: f32 = vector_slice.iter().map(|x| x * x).sum::<f32>().sqrt();
@@ -384,48 +850,38 @@ pub fn batch_normalize_vectors(vectors: &Float32Array) -> Result<Float32Array, J
         }
     }
 
-    Ok(Float32Array::from(&data[..]))
+    Ok(())
 }
 
 #[wasm_bindgen]
 pub fn compute_diversity_scores(vectors: &Float32Array, threshold: f32) -> Float32Array {
     let data: Vec<f32> = vectors.to_vec();
-    let vector_len = 1024;
-    let num_vectors = data.len() / vector_len;
-    let mut diversity_scores = vec![1.0f32; num_vectors];
+    Float32Array::from(&diversity_scores(&data, threshold)[..])
+}
+
+// Penalizes each vector by how far its similarity to the others exceeds `threshold`
+pub fn diversity_scores(data: &[f32], threshold: f32) -> Vec<f32> {
+    let vectors: Vec<&[f32]> = data.chunks_exact(1024).collect();
+    let mut diversity_scores = vec![1.0f32; vectors.len()];
 
     // Compare each vector with all others
-    for i in 0..num_vectors {
+    for (i, diversity_score) in diversity_scores.iter_mut().enumerate() {
         let mut penalty = 0.0f32;
-        let start_i = i * vector_len;
-        let end_i = start_i + vector_len;
-        let vec_i = &data[start_i..end_i];
 
-        for j in 0..num_vectors {
+        for (j, vec_j) in vectors.iter().enumerate() {
             if i != j {
-                let start_j = j * vector_len;
-                let end_j = start_j + vector_len;
-                let vec_j = &data[start_j..end_j];
-
-                // Compute cosine similarity
-                let dot_product: f32 = vec_i.iter().zip(vec_j.iter()).map(|(a, b)| a * b).sum();
-                let norm_i: f32 = vec_i.iter().map(|x| x * x).sum::<f32>().sqrt();
-                let norm_j: f32 = vec_j.iter().map(|x| x * x).sum::<f32>().sqrt();
-
-                if norm_i > 0.0 && norm_j > 0.0 {
-                    let similarity = dot_product / (norm_i * norm_j);
-                    if similarity > threshold {
-                        penalty += similarity - threshold;
-                    }
+                let similarity = cosine_similarity(vectors[i], vec_j);
+                if similarity > threshold {
+                    penalty += similarity - threshold;
                 }
             }
         }
 
         // Apply diversity penalty
-        diversity_scores[i] = (1.0 - penalty.min(1.0)).max(0.0);
+        *diversity_score = (1.0 - penalty.min(1.0)).max(0.0);
     }
 
-    Float32Array::from(&diversity_scores[..])
+    diversity_scores
 }
 
 #[wasm_bindgen]
@@ -436,19 +892,21 @@ pub fn apply_temporal_decay(scores: &Float32Array, timestamps: &[u64]) -> Float3
 
     let mut decayed_scores: Vec<f32> = scores.to_vec();
     let current_time = js_sys::Date::now() as u64;
-    let day_ms = 24 * 60 * 60 * 1000; // milliseconds in a day
 
-    for i in 0..timestamps.len() {
-        let age_days = ((current_time - timestamps[i]) / day_ms) as f32;
-
-        // Exponential decay: score * e^(-0.1 * age_days)
-        let decay_factor = (-0.1 * age_days).exp();
-        decayed_scores[i] *= decay_factor;
+    for (score, &timestamp) in decayed_scores.iter_mut().zip(timestamps.iter()) {
+        *score *= temporal_decay_factor(timestamp, current_time);
     }
 
     Float32Array::from(&decayed_scores[..])
 }
 
+// Exponential decay e^(-0.1 * age_days); future timestamps count as age zero
+pub fn temporal_decay_factor(timestamp_ms: u64, now_ms: u64) -> f32 {
+    let day_ms = 24 * 60 * 60 * 1000; // milliseconds in a day
+    let age_days = (now_ms.saturating_sub(timestamp_ms) / day_ms) as f32;
+    (-0.1 * age_days).exp()
+}
+
 // Simplified post structure for privacy filtering
 #[wasm_bindgen]
 #[derive(Clone)]
@@ -525,6 +983,21 @@ impl UserPermission {
     pub fn user_id(&self) -> u32 {
         self.user_id
     }
+
+    #[wasm_bindgen]
+    pub fn can_view(&self, post_user_id: u32, is_private: bool) -> bool {
+        // Blocked authors are hidden regardless of post visibility
+        if self.blocked_users.contains(&post_user_id) {
+            return false;
+        }
+
+        if is_private {
+            // Private posts: only if user is following the author or is the author
+            post_user_id == self.user_id || self.following.contains(&post_user_id)
+        } else {
+            true
+        }
+    }
 }
 
 #[wasm_bindgen]
@@ -541,19 +1014,7 @@ pub fn batch_privacy_filter(
     let min_len = user_ids.len().min(private_flags.len());
 
     for i in 0..min_len {
-        let post_user_id = user_ids[i];
-        let is_private = private_flags[i] != 0;
-
-        let should_include = if is_private {
-            // Private posts: only if user is following the author or is the author
-            post_user_id == user_permission.user_id()
-                || user_permission.following.contains(&post_user_id)
-        } else {
-            // Public posts: exclude if author is blocked
-            !user_permission.blocked_users.contains(&post_user_id)
-        };
-
-        if should_include {
+        if user_permission.can_view(user_ids[i], private_flags[i] != 0) {
             filtered_indices.push(i as u32);
         }
     }
@@ -561,6 +1022,103 @@ pub fn batch_privacy_filter(
     js_sys::Uint32Array::from(&filtered_indices[..])
 }
 
+// Similarity above which candidates in the same feed are penalized as redundant
+const FEED_DIVERSITY_THRESHOLD: f32 = 0.9;
+
+// Parallel per-candidate inputs for feed assembly; the shortest slice bounds the batch
+pub struct FeedCandidates<'a> {
+    pub vectors: &'a [f32],
+    pub user_ids: &'a [u32],
+    pub private_flags: &'a [u8],
+    pub timestamps: &'a [u64],
+    pub popularity: &'a [f32],
+}
+
+impl FeedCandidates<'_> {
+    pub fn len(&self) -> usize {
+        (self.vectors.len() / 1024)
+            .min(self.user_ids.len())
+            .min(self.private_flags.len())
+            .min(self.timestamps.len())
+            .min(self.popularity.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// Privacy filter, then score (with temporal decay as the recency signal), apply the
+// diversity penalty across the visible set, and keep the top k as `(index, score)`
+pub fn rank_feed(
+    scorer: &DiscoveryScorer,
+    candidates: &FeedCandidates,
+    user_permission: &UserPermission,
+    now_ms: u64,
+    k: usize,
+) -> Vec<(usize, f32)> {
+    let visible: Vec<usize> = (0..candidates.len())
+        .filter(|&i| {
+            user_permission.can_view(candidates.user_ids[i], candidates.private_flags[i] != 0)
+        })
+        .collect();
+
+    let mut visible_vectors = Vec::with_capacity(visible.len() * 1024);
+    for &i in &visible {
+        visible_vectors.extend_from_slice(&candidates.vectors[i * 1024..(i + 1) * 1024]);
+    }
+    let diversity = diversity_scores(&visible_vectors, FEED_DIVERSITY_THRESHOLD);
+
+    let mut ranked: Vec<(usize, f32)> = visible
+        .iter()
+        .zip(visible_vectors.chunks_exact(1024))
+        .zip(diversity.iter())
+        .map(|((&i, vector), &diversity_score)| {
+            let content_vector = Vector1024::from_dvector(DVector::from_column_slice(vector));
+            let recency = temporal_decay_factor(candidates.timestamps[i], now_ms);
+            let score = scorer.score_content(&content_vector, recency, candidates.popularity[i]);
+            (i, score * diversity_score)
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| compare_scores_desc(a.1, b.1));
+    ranked.truncate(k);
+    ranked
+}
+
+// Runs privacy filtering, scoring, decay, diversity, and top-k selection in one pass.
+// Returns `[index, score]` pairs for the chosen posts, best first.
+#[wasm_bindgen]
+#[allow(clippy::too_many_arguments)]
+pub fn assemble_feed(
+    user_prefs: &[f32],
+    candidate_vectors: &Float32Array,
+    post_user_ids: &js_sys::Uint32Array,
+    is_private_flags: &js_sys::Uint8Array,
+    timestamps: &[u64],
+    popularity: &[f32],
+    user_permission: &UserPermission,
+    k: usize,
+) -> Result<js_sys::Array, JsValue> {
+    let user_vector = Vector1024::new(user_prefs)?;
+    let scorer = DiscoveryScorer::new(&user_vector);
+
+    let vectors: Vec<f32> = candidate_vectors.to_vec();
+    let user_ids = post_user_ids.to_vec();
+    let private_flags = is_private_flags.to_vec();
+    let candidates = FeedCandidates {
+        vectors: &vectors,
+        user_ids: &user_ids,
+        private_flags: &private_flags,
+        timestamps,
+        popularity,
+    };
+
+    let now_ms = js_sys::Date::now() as u64;
+    let ranked = rank_feed(&scorer, &candidates, user_permission, now_ms, k);
+    Ok(indexed_scores_to_js(&ranked))
+}
+
 // VectorPool for efficient memory management
 #[wasm_bindgen]
 pub struct VectorPool {
@@ -813,6 +1371,16 @@ impl BatchVectorProcessor {
     }
 }
 
+// Descending score order that sinks NaN to the bottom instead of panicking
+fn compare_scores_desc(a: f32, b: f32) -> std::cmp::Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => std::cmp::Ordering::Equal,
+        (true, false) => std::cmp::Ordering::Greater,
+        (false, true) => std::cmp::Ordering::Less,
+        (false, false) => b.total_cmp(&a),
+    }
+}
+
 // Helper function for cosine similarity computation
 fn compute_cosine_similarity_from_arrays(vec1: &Float32Array, vec2: &Float32Array) -> f32 {
     if vec1.length() != vec2.length() {
@@ -822,13 +1390,742 @@ fn compute_cosine_similarity_from_arrays(vec1: &Float32Array, vec2: &Float32Arra
     let data1: Vec<f32> = vec1.to_vec();
     let data2: Vec<f32> = vec2.to_vec();
 
-    let dot_product: f32 = data1.iter().zip(data2.iter()).map(|(a, b)| a * b).sum();
-    let norm1: f32 = data1.iter().map(|x| x * x).sum::<f32>().sqrt();
-    let norm2: f32 = data2.iter().map(|x| x * x).sum::<f32>().sqrt();
+    cosine_similarity(&data1, &data2)
+}
 
-    if norm1 > 0.0 && norm2 > 0.0 {
-        dot_product / (norm1 * norm2)
-    } else {
-        0.0
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sin_vector(dimension: usize, offset: f32) -> Vec<f32> {
+        (0..dimension)
+            .map(|i| ((i as f32 + offset) * 0.01).sin())
+            .collect()
+    }
+
+    fn ramp_vector(offset: f32) -> Vector1024 {
+        Vector1024::new(&sin_vector(1024, offset)).unwrap()
+    }
+
+    #[test]
+    fn test_vector_n_operations() {
+        let a = VectorN::from_slice(&[3.0, 4.0, 0.0]).unwrap();
+        let b = VectorN::from_slice(&[0.0, 4.0, 3.0]).unwrap();
+
+        assert_eq!(a.dimension(), 3);
+        assert_eq!(a.magnitude(), 5.0);
+        assert_eq!(a.try_dot_product(&b).unwrap(), 16.0);
+        assert!((a.try_cosine_similarity(&b).unwrap() - 0.64).abs() < 1e-6);
+        assert!((a.try_euclidean_distance(&b).unwrap() - 18.0f32.sqrt()).abs() < 1e-6);
+        assert_eq!(a.try_manhattan_distance(&b).unwrap(), 6.0);
+        assert_eq!(a.try_add(&b).unwrap().as_slice(), &[3.0, 8.0, 3.0]);
+        assert_eq!(a.try_subtract(&b).unwrap().as_slice(), &[3.0, 0.0, -3.0]);
+        assert_eq!(a.scale(2.0).as_slice(), &[6.0, 8.0, 0.0]);
+        assert!(a.normalize().is_normalized(1e-6));
+    }
+
+    #[test]
+    fn test_vector_n_normalize_keeps_zero_vector() {
+        let zero = VectorN::from_slice(&[0.0; 768]).unwrap();
+        let normalized = zero.normalize();
+
+        assert_eq!(normalized.as_slice(), zero.as_slice());
+        assert_eq!(normalized.magnitude(), 0.0);
+        assert_eq!(
+            Vector1024::new(&[0.0; 1024])
+                .unwrap()
+                .normalize()
+                .magnitude(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_vector_n_rejects_mismatched_dimensions() {
+        let a = VectorN::from_slice(&[1.0, 2.0, 3.0]).unwrap();
+        let b = VectorN::from_slice(&[1.0, 2.0]).unwrap();
+
+        assert!(matches!(
+            a.try_dot_product(&b),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            a.try_cosine_similarity(&b),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            a.try_euclidean_distance(&b),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            a.try_manhattan_distance(&b),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(a.try_add(&b), Err(VectorError::DimensionMismatch)));
+        assert!(matches!(
+            a.try_subtract(&b),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            VectorN::from_slice(&[]),
+            Err(VectorError::InvalidInput)
+        ));
+    }
+
+    #[test]
+    fn test_vector1024_wraps_vector_n() {
+        let a = ramp_vector(0.0);
+        let b = ramp_vector(40.0);
+        let (an, bn) = (a.to_vector_n(), b.to_vector_n());
+
+        assert_eq!(an.dimension(), 1024);
+        assert_eq!(a.dot_product(&b), an.try_dot_product(&bn).unwrap());
+        assert_eq!(
+            a.euclidean_distance(&b),
+            an.try_euclidean_distance(&bn).unwrap()
+        );
+        assert_eq!(a.add(&b).as_slice(), an.try_add(&bn).unwrap().as_slice());
+    }
+
+    #[test]
+    fn test_vector_n_768_and_1536_dimensions() {
+        for &dimension in [768, 1536].iter() {
+            let a = VectorN::from_slice(&sin_vector(dimension, 0.0)).unwrap();
+            let b = VectorN::from_slice(&sin_vector(dimension, 30.0)).unwrap();
+
+            assert_eq!(a.dimension(), dimension);
+            assert!((a.try_cosine_similarity(&a).unwrap() - 1.0).abs() < 1e-5);
+            assert_eq!(
+                a.try_cosine_similarity(&b).unwrap(),
+                cosine_similarity(a.as_slice(), b.as_slice())
+            );
+            assert!(a.try_euclidean_distance(&b).unwrap() > 0.0);
+            assert_eq!(a.try_add(&b).unwrap().dimension(), dimension);
+            assert!(a.normalize().is_normalized(1e-5));
+        }
+
+        let small = VectorN::from_slice(&sin_vector(768, 0.0)).unwrap();
+        let large = VectorN::from_slice(&sin_vector(1536, 0.0)).unwrap();
+        assert!(matches!(
+            small.try_dot_product(&large),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            large.try_subtract(&small),
+            Err(VectorError::DimensionMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_batch_processor_768_and_1536_dimensions() {
+        for &dimension in [768, 1536].iter() {
+            let processor = BatchProcessor::with_dimension(10, dimension).unwrap();
+            let query = sin_vector(dimension, 0.0);
+
+            let mut data = Vec::with_capacity(dimension * 3);
+            data.extend(sin_vector(dimension, 500.0));
+            data.extend(sin_vector(dimension, 0.0));
+            data.extend(sin_vector(dimension, 50.0));
+
+            let top = processor.top_k_indexed(&query, &data, 3).unwrap();
+            let order: Vec<usize> = top.iter().map(|(idx, _)| *idx).collect();
+            assert_eq!(order[0], 1);
+            assert_eq!(processor.similarity_scores(&query, &data).unwrap().len(), 3);
+            assert_eq!(processor.centroid(&data).unwrap().dimension(), dimension);
+        }
+
+        // 768-d query against a 1536-d processor
+        let processor = BatchProcessor::with_dimension(10, 1536).unwrap();
+        let data = sin_vector(1536, 0.0);
+        assert!(matches!(
+            processor.similarity_scores(&sin_vector(768, 0.0), &data),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            processor.top_k_indexed(&sin_vector(768, 0.0), &data, 1),
+            Err(VectorError::DimensionMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_batch_processor_rejects_zero_dimension() {
+        assert!(matches!(
+            BatchProcessor::with_dimension(10, 0),
+            Err(VectorError::InvalidInput)
+        ));
+        assert_eq!(
+            BatchProcessor::with_dimension(10, 1).unwrap().dimension(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_batch_processor_custom_dimension() {
+        let processor = BatchProcessor::with_dimension(10, 3).unwrap();
+        assert_eq!(processor.dimension(), 3);
+
+        let query = [1.0, 0.0, 0.0];
+        let data = [0.0, 1.0, 0.0, 2.0, 0.0, 0.0, 1.0, 1.0, 0.0];
+
+        let similarities = processor.similarity_scores(&query, &data).unwrap();
+        assert_eq!(similarities.len(), 3);
+        assert!((similarities[1] - 1.0).abs() < 1e-6);
+
+        let top = processor.top_k_indexed(&query, &data, 2).unwrap();
+        let order: Vec<usize> = top.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(order, vec![1, 2]);
+
+        let centroid = processor.centroid(&data).unwrap();
+        assert_eq!(centroid.dimension(), 3);
+        assert!((centroid.as_slice()[0] - 1.0).abs() < 1e-6);
+
+        assert!(matches!(
+            processor.similarity_scores(ramp_vector(0.0).as_slice(), &data),
+            Err(VectorError::DimensionMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_new_normalized_cosine_matches_dot_product() {
+        let data_a: Vec<f32> = (0..1024).map(|i| i as f32 * 0.5 + 1.0).collect();
+        let data_b: Vec<f32> = (0..1024).map(|i| ((i % 7) as f32) - 3.0).collect();
+
+        let a = Vector1024::new_normalized(&data_a).unwrap();
+        let b = Vector1024::new_normalized(&data_b).unwrap();
+
+        assert!(a.is_normalized(1e-5));
+        assert!(b.is_normalized(1e-5));
+        assert!((a.cosine_similarity(&b) - a.dot_product(&b)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_is_normalized() {
+        let raw = Vector1024::new(&[2.0; 1024]).unwrap();
+        assert!(!raw.is_normalized(1e-3));
+        assert!(raw.normalize().is_normalized(1e-5));
+
+        let zero = Vector1024::new_normalized(&[0.0; 1024]).unwrap();
+        assert_eq!(zero.magnitude(), 0.0);
+        assert!(!zero.is_normalized(1e-3));
+    }
+
+    #[test]
+    fn test_top_k_indexed_points_at_most_similar() {
+        let processor = BatchProcessor::with_dimension(100, 1024).unwrap();
+        let query = ramp_vector(0.0);
+
+        // Candidate 2 is the query itself, candidate 0 is a close variant
+        let mut data = Vec::with_capacity(1024 * 4);
+        data.extend_from_slice(ramp_vector(300.0).as_slice());
+        data.extend(ramp_vector(5.0).as_slice().iter().map(|x| -x));
+        data.extend_from_slice(ramp_vector(0.0).as_slice());
+        data.extend_from_slice(ramp_vector(2.0).as_slice());
+
+        let similarities = processor
+            .similarity_scores(query.as_slice(), &data)
+            .unwrap();
+        let top = processor.top_k_indexed(query.as_slice(), &data, 2).unwrap();
+
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].0, 2);
+        assert_eq!(top[1].0, 3);
+        assert!(top[0].1 >= top[1].1);
+        for (idx, sim) in &top {
+            assert_eq!(*sim, similarities[*idx]);
+        }
+    }
+
+    #[test]
+    fn test_top_k_indexed_k_larger_than_candidates() {
+        let processor = BatchProcessor::with_dimension(100, 1024).unwrap();
+        let query = ramp_vector(0.0);
+        let data = ramp_vector(10.0).as_slice().to_vec();
+
+        let top = processor.top_k_indexed(query.as_slice(), &data, 5).unwrap();
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].0, 0);
+    }
+
+    #[test]
+    fn test_top_k_indexed_survives_zero_and_nan_vectors() {
+        let processor = BatchProcessor::with_dimension(100, 1024).unwrap();
+        let query = ramp_vector(0.0);
+
+        let mut nan_vector = ramp_vector(0.0).as_slice().to_vec();
+        nan_vector[3] = f32::NAN;
+
+        let mut data = Vec::with_capacity(1024 * 4);
+        data.extend_from_slice(&nan_vector);
+        data.extend_from_slice(&[0.0; 1024]);
+        data.extend_from_slice(ramp_vector(0.0).as_slice());
+        data.extend(ramp_vector(0.0).as_slice().iter().map(|x| -x));
+
+        let top = processor.top_k_indexed(query.as_slice(), &data, 4).unwrap();
+        let order: Vec<usize> = top.iter().map(|(idx, _)| *idx).collect();
+
+        assert_eq!(order, vec![2, 1, 3, 0]);
+        assert_eq!(top[1].1, 0.0);
+        assert!(top[3].1.is_nan());
+    }
+
+    #[test]
+    fn test_cosine_similarity_slices() {
+        assert!((cosine_similarity(&[1.0, 0.0], &[1.0, 0.0]) - 1.0).abs() < 1e-6);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 1.0]).abs() < 1e-6);
+        assert!((cosine_similarity(&[1.0, 2.0, 3.0], &[-1.0, -2.0, -3.0]) + 1.0).abs() < 1e-6);
+        assert!((cosine_similarity(&[3.0, 4.0], &[6.0, 8.0]) - 1.0).abs() < 1e-6);
+
+        // Degenerate inputs fall back to 0.0
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+        assert_eq!(cosine_similarity(&[1.0, 2.0], &[1.0, 2.0, 3.0]), 0.0);
+    }
+
+    #[test]
+    fn test_top_k_similar_slices() {
+        let query = [1.0, 0.0, 0.0];
+        let candidates = vec![
+            vec![0.0, 1.0, 0.0],
+            vec![1.0, 0.1, 0.0],
+            vec![-1.0, 0.0, 0.0],
+            vec![1.0, 0.5, 0.5],
+        ];
+
+        let top = top_k_similar(&query, &candidates, 3);
+        let order: Vec<usize> = top.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(order, vec![1, 3, 0]);
+
+        let borrowed: Vec<&[f32]> = candidates.iter().map(|c| c.as_slice()).collect();
+        assert_eq!(top_k_similar(&query, &borrowed, 3), top);
+        assert_eq!(top_k_similar(&query, &borrowed, 10).len(), 4);
+        assert!(top_k_similar::<&[f32]>(&query, &[], 3).is_empty());
+    }
+
+    #[test]
+    fn test_vector1024_cosine_matches_slice_cosine() {
+        let a = ramp_vector(0.0);
+        let b = ramp_vector(40.0);
+        assert_eq!(
+            a.cosine_similarity(&b),
+            cosine_similarity(a.as_slice(), b.as_slice())
+        );
+    }
+
+    #[test]
+    fn test_user_permission_can_view() {
+        let mut permission = UserPermission::new(1);
+        permission.add_following(2);
+        permission.add_blocked_user(3);
+        permission.add_following(3);
+
+        assert!(permission.can_view(1, true));
+        assert!(permission.can_view(2, true));
+        assert!(permission.can_view(4, false));
+        assert!(!permission.can_view(4, true));
+        assert!(!permission.can_view(3, false));
+        assert!(!permission.can_view(3, true));
+    }
+
+    #[test]
+    fn test_temporal_decay_factor() {
+        let day_ms = 24 * 60 * 60 * 1000;
+        let now = 100 * day_ms;
+
+        assert_eq!(temporal_decay_factor(now, now), 1.0);
+        assert_eq!(temporal_decay_factor(now + day_ms, now), 1.0);
+        assert!((temporal_decay_factor(now - 10 * day_ms, now) - (-1.0f32).exp()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_rank_feed_never_returns_blocked_author() {
+        let user_vector = ramp_vector(0.0);
+        let scorer = DiscoveryScorer::new(&user_vector);
+        let mut permission = UserPermission::new(1);
+        permission.add_blocked_user(99);
+
+        // Candidate 0 is a perfect match from a blocked author
+        let mut vectors = Vec::with_capacity(1024 * 4);
+        vectors.extend_from_slice(ramp_vector(0.0).as_slice());
+        vectors.extend_from_slice(ramp_vector(200.0).as_slice());
+        vectors.extend_from_slice(ramp_vector(400.0).as_slice());
+        vectors.extend_from_slice(ramp_vector(600.0).as_slice());
+        let now = 1_700_000_000_000;
+
+        let candidates = FeedCandidates {
+            vectors: &vectors,
+            user_ids: &[99, 2, 3, 4],
+            private_flags: &[0, 0, 0, 1],
+            timestamps: &[now, now, now, now],
+            popularity: &[1.0, 0.1, 0.1, 1.0],
+        };
+
+        let ranked = rank_feed(&scorer, &candidates, &permission, now, 10);
+        let indices: Vec<usize> = ranked.iter().map(|(idx, _)| *idx).collect();
+
+        // Blocked author and the unfollowed private post are filtered out
+        assert_eq!(indices.len(), 2);
+        assert!(!indices.contains(&0));
+        assert!(!indices.contains(&3));
+        assert!(ranked[0].1 >= ranked[1].1);
+
+        assert_eq!(
+            rank_feed(&scorer, &candidates, &permission, now, 1).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_rank_feed_penalizes_duplicates_and_old_posts() {
+        let user_vector = ramp_vector(0.0);
+        let scorer = DiscoveryScorer::new(&user_vector);
+        let permission = UserPermission::new(1);
+        let day_ms = 24 * 60 * 60 * 1000;
+        let now = 1_700_000_000_000;
+
+        let mut vectors = Vec::with_capacity(1024 * 3);
+        vectors.extend_from_slice(ramp_vector(100.0).as_slice());
+        vectors.extend_from_slice(ramp_vector(100.0).as_slice());
+        vectors.extend_from_slice(ramp_vector(100.0).as_slice());
+
+        let fresh = FeedCandidates {
+            vectors: &vectors[..1024],
+            user_ids: &[2],
+            private_flags: &[0],
+            timestamps: &[now],
+            popularity: &[0.5],
+        };
+        let old = FeedCandidates {
+            timestamps: &[now - 30 * day_ms],
+            ..fresh
+        };
+        let duplicated = FeedCandidates {
+            vectors: &vectors,
+            user_ids: &[2, 3, 4],
+            private_flags: &[0, 0, 0],
+            timestamps: &[now, now, now],
+            popularity: &[0.5, 0.5, 0.5],
+        };
+
+        let fresh_score = rank_feed(&scorer, &fresh, &permission, now, 1)[0].1;
+        let old_score = rank_feed(&scorer, &old, &permission, now, 1)[0].1;
+        let duplicate_score = rank_feed(&scorer, &duplicated, &permission, now, 1)[0].1;
+
+        assert!(old_score < fresh_score);
+        assert!(duplicate_score < fresh_score);
+    }
+
+    fn mmr_candidates() -> Vec<f32> {
+        // 0 and 1 are near-duplicates closest to the user; 2 is a distinct,
+        // slightly less relevant item
+        let mut data = Vec::with_capacity(1024 * 3);
+        data.extend_from_slice(ramp_vector(5.0).as_slice());
+        data.extend_from_slice(ramp_vector(6.0).as_slice());
+        data.extend_from_slice(ramp_vector(60.0).as_slice());
+        data
+    }
+
+    #[test]
+    fn test_rerank_mmr_pure_relevance_ordering() {
+        let scorer = DiscoveryScorer::new(&ramp_vector(0.0));
+        let data = mmr_candidates();
+
+        assert_eq!(scorer.mmr_order(&data, 1.0, 3), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_rerank_mmr_low_lambda_skips_near_duplicates() {
+        let scorer = DiscoveryScorer::new(&ramp_vector(0.0));
+        let data = mmr_candidates();
+
+        let order = scorer.mmr_order(&data, 0.3, 2);
+        assert_eq!(order, vec![0, 2]);
+    }
+
+    #[test]
+    fn test_rerank_mmr_credits_anti_correlated_candidates() {
+        // User prefers e0 + e1. Candidate 0 (e0) is picked first; 1 (e1 + e2)
+        // is orthogonal to it, and 2 (e1 - 0.5 e0) is less relevant than 1 but
+        // points away from 0, which should count as diversity rather than zero
+        let mut user = vec![0.0f32; 1024];
+        user[0] = 1.0;
+        user[1] = 1.0;
+        let scorer = DiscoveryScorer::new(&Vector1024::new(&user).unwrap());
+
+        let mut data = vec![0.0f32; 1024 * 3];
+        data[0] = 1.0;
+        data[1024 + 1] = 1.0;
+        data[1024 + 2] = 1.0;
+        data[2048] = -0.5;
+        data[2048 + 1] = 1.0;
+
+        assert_eq!(scorer.mmr_order(&data, 1.0, 3), vec![0, 1, 2]);
+        assert_eq!(scorer.mmr_order(&data, 0.5, 2), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_rerank_mmr_k_larger_than_candidates() {
+        let scorer = DiscoveryScorer::new(&ramp_vector(0.0));
+        let data = mmr_candidates();
+
+        let mut order = scorer.mmr_order(&data, 0.5, 10);
+        assert_eq!(order.len(), 3);
+        order.sort_unstable();
+        assert_eq!(order, vec![0, 1, 2]);
+        assert!(scorer.mmr_order(&[], 0.5, 3).is_empty());
+    }
+
+    fn assert_weights(scorer: &DiscoveryScorer, expected: [f32; 4]) {
+        let factors = ["relevance", "recency", "popularity", "diversity"];
+        for (factor, expected) in factors.iter().zip(expected.iter()) {
+            assert!(
+                (scorer.weight(factor) - expected).abs() < 1e-6,
+                "{} weight was {}",
+                factor,
+                scorer.weight(factor)
+            );
+        }
+    }
+
+    #[test]
+    fn test_discovery_scorer_presets() {
+        let user_vector = ramp_vector(0.0);
+
+        let chronological = DiscoveryScorer::with_preset(&user_vector, "chronological").unwrap();
+        assert_weights(&chronological, [0.1, 0.7, 0.1, 0.1]);
+        let relevance = DiscoveryScorer::with_preset(&user_vector, "relevance").unwrap();
+        assert_weights(&relevance, [0.7, 0.1, 0.1, 0.1]);
+        let balanced = DiscoveryScorer::with_preset(&user_vector, "balanced").unwrap();
+        assert_weights(&balanced, [0.4, 0.3, 0.2, 0.1]);
+
+        // Balanced matches the constructor defaults
+        assert_weights(&DiscoveryScorer::new(&user_vector), [0.4, 0.3, 0.2, 0.1]);
+        assert!(preset_weights("trending").is_none());
+    }
+
+    #[test]
+    fn test_discovery_scorer_presets_shift_order() {
+        let user_vector = ramp_vector(0.0);
+        let relevant_but_old = ramp_vector(0.0);
+        let fresh_but_unrelated = Vector1024::new(
+            &ramp_vector(0.0)
+                .as_slice()
+                .iter()
+                .map(|x| -x)
+                .collect::<Vec<f32>>(),
+        )
+        .unwrap();
+
+        let chronological = DiscoveryScorer::with_preset(&user_vector, "chronological").unwrap();
+        assert!(
+            chronological.score_content(&fresh_but_unrelated, 1.0, 0.5)
+                > chronological.score_content(&relevant_but_old, 0.0, 0.5)
+        );
+
+        let relevance = DiscoveryScorer::with_preset(&user_vector, "relevance").unwrap();
+        assert!(
+            relevance.score_content(&relevant_but_old, 0.0, 0.5)
+                > relevance.score_content(&fresh_but_unrelated, 1.0, 0.5)
+        );
+    }
+
+    #[test]
+    fn test_normalize_vectors_in_place() {
+        let mut data = Vec::with_capacity(1024 * 2);
+        data.extend_from_slice(&[2.0; 1024]);
+        data.extend_from_slice(&[0.0; 1024]);
+
+        normalize_vectors_in_place(&mut data, false).unwrap();
+
+        let first = Vector1024::new(&data[..1024]).unwrap();
+        assert!(first.is_normalized(1e-5));
+        // Zero vectors stay zero rather than dividing by zero
+        assert!(data[1024..].iter().all(|&x| x == 0.0));
+    }
+
+    #[test]
+    fn test_normalize_vectors_rejects_nan_with_index() {
+        let mut data = Vec::with_capacity(1024 * 3);
+        data.extend_from_slice(&[1.0; 1024]);
+        data.extend_from_slice(&[0.0; 1024]);
+        data.extend_from_slice(&[1.0; 1024]);
+        data[2 * 1024 + 5] = f32::NAN;
+
+        let err = normalize_vectors_in_place(&mut data, false).unwrap_err();
+        assert!(matches!(err, VectorError::NonFiniteVector(2)));
+        assert_eq!(err.to_string(), "Vector 2 contains NaN or infinite values");
+    }
+
+    #[test]
+    fn test_normalize_vectors_skip_invalid_zero_fills() {
+        let mut data = Vec::with_capacity(1024 * 2);
+        data.extend_from_slice(&[1.0; 1024]);
+        data.extend_from_slice(&[3.0; 1024]);
+        data[7] = f32::INFINITY;
+
+        normalize_vectors_in_place(&mut data, true).unwrap();
+
+        assert!(data[..1024].iter().all(|&x| x == 0.0));
+        assert!(Vector1024::new(&data[1024..]).unwrap().is_normalized(1e-5));
+        assert!(data.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn test_normalize_vectors_requires_whole_vectors() {
+        let mut data = vec![1.0; 1000];
+        assert!(matches!(
+            normalize_vectors_in_place(&mut data, false),
+            Err(VectorError::DimensionMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_score_batch_uses_1024_stride() {
+        let user_vector = Vector1024::new(&[1.0; 1024]).unwrap();
+        let scorer = DiscoveryScorer::new(&user_vector);
+
+        let mut content = Vec::with_capacity(1024 * 2);
+        content.extend_from_slice(&[0.8; 1024]);
+        content.extend(ramp_vector(0.0).as_slice().iter().map(|x| x.abs()));
+
+        let scores = scorer.score_batch(&content, &[0.9, 0.2], &[0.8, 0.1]);
+        assert_eq!(scores.len(), 2);
+
+        // Identical direction: relevance 1.0 triggers the 0.8 diversity penalty
+        let expected_first = 0.4 * 1.0 + 0.3 * 0.9 + 0.2 * 0.8 + 0.1 * 0.8;
+        assert!((scores[0] - expected_first).abs() < 1e-5);
+
+        let second = Vector1024::new(&content[1024..]).unwrap();
+        assert!(scores[1] > 0.0);
+        assert_eq!(scores[1], scorer.score_content(&second, 0.2, 0.1));
+        assert!(scores[0] > scores[1]);
+    }
+
+    #[test]
+    fn test_score_batch_missing_signals_score_zero() {
+        let scorer = DiscoveryScorer::new(&ramp_vector(0.0));
+        let mut content = Vec::with_capacity(1024 * 2);
+        content.extend_from_slice(ramp_vector(0.0).as_slice());
+        content.extend_from_slice(ramp_vector(0.0).as_slice());
+
+        let scores = scorer.score_batch(&content, &[0.5], &[0.5, 0.5]);
+        assert_eq!(scores.len(), 2);
+        assert!(scores[0] > 0.0);
+        assert_eq!(scores[1], 0.0);
+    }
+
+    #[test]
+    fn test_compare_scores_desc_sinks_nan() {
+        let mut scores = [0.2, f32::NAN, 0.9, -0.5, f32::NAN, 0.4];
+        scores.sort_by(|a, b| compare_scores_desc(*a, *b));
+
+        assert_eq!(&scores[..4], &[0.9, 0.4, 0.2, -0.5]);
+        assert!(scores[4].is_nan() && scores[5].is_nan());
+    }
+
+    #[test]
+    fn test_weighted_centroid_uniform_matches_centroid() {
+        let processor = BatchProcessor::with_dimension(100, 1024).unwrap();
+        let mut data = Vec::with_capacity(1024 * 3);
+        data.extend_from_slice(&[1.0; 1024]);
+        data.extend_from_slice(&[3.0; 1024]);
+        data.extend_from_slice(ramp_vector(0.0).as_slice());
+
+        let centroid = processor.centroid(&data).unwrap();
+        let weighted = processor
+            .weighted_centroid(&data, &[2.0, 2.0, 2.0])
+            .unwrap();
+
+        assert!(centroid.try_euclidean_distance(&weighted).unwrap() < 1e-5);
+    }
+
+    #[test]
+    fn test_weighted_centroid_skews_toward_heavy_vector() {
+        let processor = BatchProcessor::with_dimension(100, 1024).unwrap();
+        let mut data = Vec::with_capacity(1024 * 2);
+        data.extend_from_slice(&[0.0; 1024]);
+        data.extend_from_slice(&[10.0; 1024]);
+
+        let weighted = processor.weighted_centroid(&data, &[1.0, 9.0]).unwrap();
+        assert!(weighted.as_slice().iter().all(|v| (v - 9.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn test_weighted_centroid_rejects_bad_weights() {
+        let processor = BatchProcessor::with_dimension(100, 1024).unwrap();
+        let mut data = Vec::with_capacity(1024 * 2);
+        data.extend_from_slice(&[1.0; 1024]);
+        data.extend_from_slice(&[2.0; 1024]);
+
+        assert!(matches!(
+            processor.weighted_centroid(&data, &[1.0]),
+            Err(VectorError::DimensionMismatch)
+        ));
+        assert!(matches!(
+            processor.weighted_centroid(&data, &[1.0, -1.0]),
+            Err(VectorError::InvalidInput)
+        ));
+        assert!(matches!(
+            processor.weighted_centroid(&[], &[]),
+            Err(VectorError::InvalidInput)
+        ));
+    }
+
+    #[test]
+    fn test_discovery_scorer_zero_epsilon_is_deterministic() {
+        let user_vector = ramp_vector(0.0);
+        let baseline = DiscoveryScorer::new(&user_vector);
+        let mut scorer = DiscoveryScorer::new(&user_vector);
+        scorer.set_epsilon(0.0);
+        scorer.set_seed(7);
+
+        for i in 0..10 {
+            let content = ramp_vector(i as f32 * 10.0);
+            let expected = baseline.score_content(&content, 0.5, 0.5);
+            assert_eq!(scorer.score_content(&content, 0.5, 0.5), expected);
+        }
+    }
+
+    #[test]
+    fn test_discovery_scorer_full_epsilon_explores() {
+        let user_vector = ramp_vector(0.0);
+        let baseline = DiscoveryScorer::new(&user_vector);
+        let mut scorer = DiscoveryScorer::new(&user_vector);
+        scorer.set_epsilon(1.0);
+        scorer.set_seed(42);
+
+        let contents: Vec<Vector1024> = (0..20).map(|i| ramp_vector(i as f32 * 25.0)).collect();
+        let rank = |scores: &[f32]| {
+            let mut order: Vec<usize> = (0..scores.len()).collect();
+            order.sort_by(|&a, &b| scores[b].total_cmp(&scores[a]));
+            order
+        };
+
+        let deterministic: Vec<f32> = contents
+            .iter()
+            .map(|c| baseline.score_content(c, 0.5, 0.5))
+            .collect();
+        let explored: Vec<f32> = contents
+            .iter()
+            .map(|c| scorer.score_content(c, 0.5, 0.5))
+            .collect();
+
+        assert!(explored.iter().all(|s| (0.0..=1.0).contains(s)));
+        assert_ne!(rank(&explored), rank(&deterministic));
+
+        // Same seed reproduces the same exploration scores
+        scorer.set_seed(42);
+        let replayed: Vec<f32> = contents
+            .iter()
+            .map(|c| scorer.score_content(c, 0.5, 0.5))
+            .collect();
+        assert_eq!(replayed, explored);
+    }
+
+    #[test]
+    fn test_discovery_scorer_epsilon_is_clamped() {
+        let mut scorer = DiscoveryScorer::new(&ramp_vector(0.0));
+        scorer.set_epsilon(1.5);
+        assert_eq!(scorer.epsilon(), 1.0);
+        scorer.set_epsilon(-0.5);
+        assert_eq!(scorer.epsilon(), 0.0);
     }
 }